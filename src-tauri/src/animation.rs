@@ -0,0 +1,195 @@
+//! Lossless animated image encoders (APNG and animated WebP)
+//!
+//! Unlike GIF these keep full 24-bit color plus alpha, so gradients in UI
+//! recordings don't band. Both encoders take a display time per frame, load
+//! one frame at a time from the frame source, and stop early when
+//! `on_progress` returns an error (e.g. the export was cancelled).
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use image::codecs::webp::WebPEncoder;
+use image::ExtendedColorType;
+
+use crate::frame_source::FrameSource;
+
+/// Encode frames as an animated PNG
+/// `num_plays` = 0 loops forever; `quality` (1-100) selects deflate effort
+pub fn encode_apng<F>(
+    frames: &dyn FrameSource,
+    delays_ms: &[u32],
+    num_plays: u32,
+    quality: u32,
+    path: &Path,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    if frames.is_empty() {
+        return Err("No frames to encode".to_string());
+    }
+
+    let (width, height) = frames.dimensions();
+    let total = frames.len();
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png_compression(quality));
+    encoder
+        .set_animated(total as u32, num_plays)
+        .map_err(|e| e.to_string())?;
+
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    for i in 0..total {
+        let frame = frames.frame(i)?;
+        let delay = delays_ms.get(i).copied().unwrap_or(100);
+        writer
+            .set_frame_delay(delay.clamp(1, u16::MAX as u32) as u16, 1000)
+            .map_err(|e| e.to_string())?;
+        writer
+            .write_image_data(frame.as_raw())
+            .map_err(|e| e.to_string())?;
+        on_progress(i + 1, total)?;
+    }
+    writer.finish().map_err(|e| e.to_string())
+}
+
+/// Deflate effort for a quality (1-100); PNG stays lossless either way
+pub fn png_compression(quality: u32) -> png::Compression {
+    match quality.clamp(1, 100) {
+        1..=39 => png::Compression::Fast,
+        40..=79 => png::Compression::Balanced,
+        _ => png::Compression::High,
+    }
+}
+
+/// Encode frames as a lossless animated WebP
+/// `loop_count` = 0 loops forever
+pub fn encode_webp<F>(
+    frames: &dyn FrameSource,
+    delays_ms: &[u32],
+    loop_count: u16,
+    path: &Path,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    if frames.is_empty() {
+        return Err("No frames to encode".to_string());
+    }
+
+    let (width, height) = frames.dimensions();
+    let total = frames.len();
+
+    let mut body = Vec::new();
+
+    // VP8X: extended header with animation + alpha flags and canvas size
+    let mut vp8x = Vec::with_capacity(10);
+    vp8x.push(0x02 | 0x10);
+    vp8x.extend_from_slice(&[0, 0, 0]);
+    vp8x.extend_from_slice(&u24(width - 1));
+    vp8x.extend_from_slice(&u24(height - 1));
+    riff_chunk(&mut body, b"VP8X", &vp8x);
+
+    // ANIM: transparent background, loop count
+    let mut anim = Vec::with_capacity(6);
+    anim.extend_from_slice(&[0, 0, 0, 0]);
+    anim.extend_from_slice(&loop_count.to_le_bytes());
+    riff_chunk(&mut body, b"ANIM", &anim);
+
+    for i in 0..total {
+        let frame = frames.frame(i)?;
+        let mut still = Vec::new();
+        WebPEncoder::new_lossless(&mut still)
+            .encode(frame.as_raw(), width, height, ExtendedColorType::Rgba8)
+            .map_err(|e| e.to_string())?;
+
+        let mut anmf = Vec::with_capacity(still.len() + 16);
+        anmf.extend_from_slice(&u24(0)); // x offset / 2
+        anmf.extend_from_slice(&u24(0)); // y offset / 2
+        anmf.extend_from_slice(&u24(width - 1));
+        anmf.extend_from_slice(&u24(height - 1));
+        // ANMF duration is a 24-bit millisecond count
+        let duration_ms = delays_ms.get(i).copied().unwrap_or(100);
+        anmf.extend_from_slice(&u24(duration_ms.clamp(1, 0xFF_FFFF)));
+        anmf.push(0x02); // no blending, no disposal
+        anmf.extend_from_slice(image_chunks(&still)?);
+        riff_chunk(&mut body, b"ANMF", &anmf);
+
+        on_progress(i + 1, total)?;
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 12);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+    out.extend_from_slice(b"WEBP");
+    out.extend_from_slice(&body);
+
+    fs::write(path, out).map_err(|e| e.to_string())
+}
+
+/// Strip the RIFF/WEBP header from a still WebP, leaving its bitstream chunk(s)
+fn image_chunks(still: &[u8]) -> Result<&[u8], String> {
+    if still.len() < 20 || &still[0..4] != b"RIFF" || &still[8..12] != b"WEBP" {
+        return Err("Unexpected WebP encoder output".to_string());
+    }
+    // A plain lossless still is a single VP8L chunk; anything else can't go in ANMF as-is
+    if &still[12..16] != b"VP8L" {
+        return Err("Unexpected WebP chunk layout".to_string());
+    }
+    Ok(&still[12..])
+}
+
+fn riff_chunk(out: &mut Vec<u8>, fourcc: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(fourcc);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+fn u24(v: u32) -> [u8; 3] {
+    let b = v.to_le_bytes();
+    [b[0], b[1], b[2]]
+}
+
+/// Whether a PNG file is animated (has an acTL chunk before the image data)
+pub fn is_animated_png(path: &Path) -> bool {
+    let Ok(data) = read_head(path, 4096) else {
+        return false;
+    };
+    let mut pos = 8;
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        let kind = &data[pos + 4..pos + 8];
+        if kind == b"acTL" {
+            return true;
+        }
+        if kind == b"IDAT" {
+            return false;
+        }
+        pos += 12 + len as usize;
+    }
+    false
+}
+
+/// Whether a WebP file is animated (VP8X header with the animation flag)
+pub fn is_animated_webp(path: &Path) -> bool {
+    let Ok(data) = read_head(path, 21) else {
+        return false;
+    };
+    data.len() >= 21 && &data[12..16] == b"VP8X" && data[20] & 0x02 != 0
+}
+
+fn read_head(path: &Path, len: usize) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    let mut buf = Vec::with_capacity(len);
+    File::open(path)?.take(len as u64).read_to_end(&mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,380 @@
+//! Annotations burned into recordings and screenshots
+//!
+//! Captions, callouts and pen strokes are kept as data (`Annotation`) that the
+//! frontend edits, and only rasterized when a frame is exported or a screenshot
+//! is saved, so the image is encoded just once.
+
+use std::f32::consts::PI;
+
+use image::{Rgba, RgbaImage};
+
+use crate::draw;
+use crate::types::{Annotation, TimedAnnotation};
+
+/// Padding around text with a background, relative to the font size
+const TEXT_PADDING: f32 = 0.3;
+/// Arrow head length per stroke width (but at least `ARROW_HEAD_MIN` px), and
+/// the angle between each wing and the shaft
+const ARROW_HEAD_SCALE: f32 = 4.0;
+const ARROW_HEAD_MIN: f32 = 10.0;
+const ARROW_HEAD_ANGLE: f32 = PI / 6.0;
+const STEP_TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// Burn annotations into a screenshot, in list order. Coordinates and sizes are
+/// multiplied by `scale`, e.g. from selection (logical) to captured pixels.
+pub fn render(img: &mut RgbaImage, annotations: &[Annotation], scale: f32) {
+    let mut step = 0;
+    for annotation in annotations {
+        if matches!(annotation, Annotation::Step { .. }) {
+            step += 1;
+        }
+        draw(img, &scaled(annotation, scale), step);
+    }
+}
+
+/// Draw the annotations shown on source frame `index`, in list order. Steps keep
+/// their number in the whole list, so hidden ones don't renumber the rest.
+pub fn draw_for_frame(frame: &mut RgbaImage, annotations: &[TimedAnnotation], index: usize) {
+    let mut step = 0;
+    for timed in annotations {
+        if matches!(timed.annotation, Annotation::Step { .. }) {
+            step += 1;
+        }
+        if (timed.start_frame..timed.end_frame).contains(&index) {
+            draw(frame, &timed.annotation, step);
+        }
+    }
+}
+
+/// `step` is the number shown if this is a step badge
+fn draw(img: &mut RgbaImage, annotation: &Annotation, step: u32) {
+    match annotation {
+        Annotation::Text {
+            x,
+            y,
+            text,
+            font_size,
+            color,
+            background,
+        } => draw_text_block(img, (*x, *y), text, *font_size, *color, *background),
+        Annotation::Rect {
+            x,
+            y,
+            width,
+            height,
+            color,
+            stroke_width,
+            fill,
+        } => {
+            let (x0, x1) = (x.min(x + width), x.max(x + width));
+            let (y0, y1) = (y.min(y + height), y.max(y + height));
+            // Signed distance to the outline, negative inside
+            let outline_distance = |px: f32, py: f32| {
+                let dx = (x0 - px).max(px - x1);
+                let dy = (y0 - py).max(py - y1);
+                dx.max(0.0).hypot(dy.max(0.0)) + dx.max(dy).min(0.0)
+            };
+            draw_shape(
+                img,
+                (x0, y0),
+                (x1, y1),
+                *color,
+                *stroke_width,
+                *fill,
+                outline_distance,
+            );
+        }
+        Annotation::Ellipse {
+            x,
+            y,
+            width,
+            height,
+            color,
+            stroke_width,
+            fill,
+        } => {
+            let (cx, cy) = (x + width / 2.0, y + height / 2.0);
+            let (rx, ry) = ((width / 2.0).abs(), (height / 2.0).abs());
+            if rx <= 0.0 || ry <= 0.0 {
+                return;
+            }
+            // First-order estimate of the signed distance to the outline
+            let outline_distance = |px: f32, py: f32| {
+                let (dx, dy) = (px - cx, py - cy);
+                let f = (dx / rx).powi(2) + (dy / ry).powi(2) - 1.0;
+                let gradient = (2.0 * dx / (rx * rx)).hypot(2.0 * dy / (ry * ry));
+                if gradient > f32::EPSILON {
+                    f / gradient
+                } else {
+                    -rx.min(ry)
+                }
+            };
+            draw_shape(
+                img,
+                (cx - rx, cy - ry),
+                (cx + rx, cy + ry),
+                *color,
+                *stroke_width,
+                *fill,
+                outline_distance,
+            );
+        }
+        Annotation::Arrow {
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            color,
+            stroke_width,
+        } => draw_arrow(
+            img,
+            (*from_x, *from_y),
+            (*to_x, *to_y),
+            *color,
+            *stroke_width,
+        ),
+        Annotation::Freehand {
+            points,
+            color,
+            stroke_width,
+        } => draw_freehand(img, points, *color, *stroke_width),
+        Annotation::Step {
+            x,
+            y,
+            radius,
+            color,
+        } => draw_step(img, (*x, *y), *radius, *color, step),
+    }
+}
+
+fn scaled(annotation: &Annotation, scale: f32) -> Annotation {
+    let mut annotation = annotation.clone();
+    if scale == 1.0 {
+        return annotation;
+    }
+    match &mut annotation {
+        Annotation::Text {
+            x, y, font_size, ..
+        } => {
+            for v in [x, y, font_size] {
+                *v *= scale;
+            }
+        }
+        Annotation::Rect {
+            x,
+            y,
+            width,
+            height,
+            stroke_width,
+            ..
+        }
+        | Annotation::Ellipse {
+            x,
+            y,
+            width,
+            height,
+            stroke_width,
+            ..
+        } => {
+            for v in [x, y, width, height, stroke_width] {
+                *v *= scale;
+            }
+        }
+        Annotation::Arrow {
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            stroke_width,
+            ..
+        } => {
+            for v in [from_x, from_y, to_x, to_y, stroke_width] {
+                *v *= scale;
+            }
+        }
+        Annotation::Freehand {
+            points,
+            stroke_width,
+            ..
+        } => {
+            for point in points.iter_mut() {
+                point[0] *= scale;
+                point[1] *= scale;
+            }
+            *stroke_width *= scale;
+        }
+        Annotation::Step { x, y, radius, .. } => {
+            for v in [x, y, radius] {
+                *v *= scale;
+            }
+        }
+    }
+    annotation
+}
+
+fn draw_text_block(
+    img: &mut RgbaImage,
+    (x, y): (f32, f32),
+    text: &str,
+    font_size: f32,
+    color: [u8; 4],
+    background: Option<[u8; 4]>,
+) {
+    let size = font_size.max(1.0);
+    let lines: Vec<&str> = text.lines().collect();
+    let line_height = draw::measure_text("", size).1 as f32;
+
+    if let Some(background) = background {
+        let width = lines
+            .iter()
+            .map(|line| draw::measure_text(line, size).0)
+            .max()
+            .unwrap_or(0) as f32;
+        let padding = size * TEXT_PADDING;
+        draw::fill_rounded_rect(
+            img,
+            (x - padding).round() as i64,
+            (y - padding).round() as i64,
+            (width + 2.0 * padding).round() as u32,
+            (line_height * lines.len() as f32 + 2.0 * padding).round() as u32,
+            padding,
+            Rgba(background),
+        );
+    }
+    for (i, line) in lines.iter().enumerate() {
+        draw::draw_text(img, line, x, y + i as f32 * line_height, size, Rgba(color));
+    }
+}
+
+/// Closed shape in the box `(x0, y0)..(x1, y1)`: a stroke centered on the
+/// outline over an optional fill. `outline_distance` is negative inside.
+fn draw_shape(
+    img: &mut RgbaImage,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    color: [u8; 4],
+    stroke_width: f32,
+    fill: Option<[u8; 4]>,
+    outline_distance: impl Fn(f32, f32) -> f32,
+) {
+    if let Some(fill) = fill {
+        draw::fill_coverage(img, (x0, y0), (x1, y1), Rgba(fill), |px, py| {
+            (0.5 - outline_distance(px, py)).clamp(0.0, 1.0)
+        });
+    }
+    if stroke_width > 0.0 {
+        let half = stroke_width / 2.0;
+        let reach = half + 1.0;
+        draw::fill_coverage(
+            img,
+            (x0 - reach, y0 - reach),
+            (x1 + reach, y1 + reach),
+            Rgba(color),
+            |px, py| (half + 0.5 - outline_distance(px, py).abs()).clamp(0.0, 1.0),
+        );
+    }
+}
+
+fn draw_arrow(
+    img: &mut RgbaImage,
+    from: (f32, f32),
+    to: (f32, f32),
+    color: [u8; 4],
+    stroke_width: f32,
+) {
+    let half = stroke_width.max(1.0) / 2.0;
+    let head = (stroke_width * ARROW_HEAD_SCALE).max(ARROW_HEAD_MIN);
+    // Wings point back along the shaft
+    let back = (from.1 - to.1).atan2(from.0 - to.0);
+    let wing = |angle: f32| (to.0 + head * angle.cos(), to.1 + head * angle.sin());
+    let left = wing(back - ARROW_HEAD_ANGLE);
+    let right = wing(back + ARROW_HEAD_ANGLE);
+
+    let reach = head + half + 1.0;
+    draw::fill_coverage(
+        img,
+        (from.0.min(to.0) - reach, from.1.min(to.1) - reach),
+        (from.0.max(to.0) + reach, from.1.max(to.1) + reach),
+        Rgba(color),
+        |px, py| {
+            let distance = draw::segment_distance(px, py, from, to)
+                .min(draw::segment_distance(px, py, to, left))
+                .min(draw::segment_distance(px, py, to, right));
+            (half + 0.5 - distance).clamp(0.0, 1.0)
+        },
+    );
+}
+
+fn draw_freehand(img: &mut RgbaImage, points: &[[f32; 2]], color: [u8; 4], stroke_width: f32) {
+    let segments: Vec<((f32, f32), (f32, f32))> = match points {
+        [] => return,
+        [p] => vec![((p[0], p[1]), (p[0], p[1]))],
+        _ => points
+            .windows(2)
+            .map(|w| ((w[0][0], w[0][1]), (w[1][0], w[1][1])))
+            .collect(),
+    };
+    let half = stroke_width.max(1.0) / 2.0;
+    let reach = half + 1.0;
+    let (mut x0, mut y0, mut x1, mut y1) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for p in points {
+        (x0, y0) = (x0.min(p[0]), y0.min(p[1]));
+        (x1, y1) = (x1.max(p[0]), y1.max(p[1]));
+    }
+    let left = (x0 - reach).floor().max(0.0) as u32;
+    let top = (y0 - reach).floor().max(0.0) as u32;
+    let right = ((x1 + reach).ceil().max(0.0) as u32).min(img.width());
+    let bottom = ((y1 + reach).ceil().max(0.0) as u32).min(img.height());
+    if left >= right || top >= bottom {
+        return;
+    }
+
+    // Coverage is the max over all segments, so overlaps and joints blend once
+    let width = (right - left) as usize;
+    let mut mask = vec![0.0f32; width * (bottom - top) as usize];
+    for (a, b) in segments {
+        let sx0 = ((a.0.min(b.0) - reach).floor().max(left as f32) as u32).min(right);
+        let sy0 = ((a.1.min(b.1) - reach).floor().max(top as f32) as u32).min(bottom);
+        let sx1 = ((a.0.max(b.0) + reach).ceil().max(0.0) as u32).min(right);
+        let sy1 = ((a.1.max(b.1) + reach).ceil().max(0.0) as u32).min(bottom);
+        for y in sy0..sy1 {
+            for x in sx0..sx1 {
+                let distance = draw::segment_distance(x as f32 + 0.5, y as f32 + 0.5, a, b);
+                let coverage = (half + 0.5 - distance).clamp(0.0, 1.0);
+                let i = (y - top) as usize * width + (x - left) as usize;
+                mask[i] = mask[i].max(coverage);
+            }
+        }
+    }
+    for (i, &coverage) in mask.iter().enumerate() {
+        if coverage > 0.0 {
+            let x = left as i64 + (i % width) as i64;
+            let y = top as i64 + (i / width) as i64;
+            draw::blend_pixel(img, x, y, Rgba(color), coverage);
+        }
+    }
+}
+
+/// Filled circle with its number
+fn draw_step(img: &mut RgbaImage, (x, y): (f32, f32), radius: f32, color: [u8; 4], number: u32) {
+    let radius = radius.max(1.0);
+    draw::fill_coverage(
+        img,
+        (x - radius - 1.0, y - radius - 1.0),
+        (x + radius + 1.0, y + radius + 1.0),
+        Rgba(color),
+        |px, py| (radius + 0.5 - (px - x).hypot(py - y)).clamp(0.0, 1.0),
+    );
+    let label = number.to_string();
+    // Two-digit numbers get a smaller font to stay inside the circle
+    let size = radius * if label.len() > 1 { 0.9 } else { 1.2 };
+    let (w, h) = draw::measure_text(&label, size);
+    draw::draw_text(
+        img,
+        &label,
+        x - w as f32 / 2.0,
+        y - h as f32 / 2.0,
+        size,
+        STEP_TEXT_COLOR,
+    );
+}
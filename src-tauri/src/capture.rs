@@ -1,10 +1,27 @@
 //! Screen capture abstraction using xcap
 //!
-//! Provides a unified API for screen capture operations.
+//! Provides a unified API for screen capture operations. Windows of apps on the
+//! exclusion list are masked in every area capture, before the image leaves
+//! this module.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use image::RgbaImage;
 use xcap::Monitor;
 
+use crate::config::{self, ExclusionConfig};
+use crate::redact;
+use crate::types::Redaction;
+
+/// How long a lookup of the excluded windows is reused. Listing windows takes
+/// longer than a frame at high fps; a window that moves may show through at
+/// its new position for up to this long.
+const EXCLUDED_WINDOWS_TTL: Duration = Duration::from_millis(250);
+
+/// Window bounds (x, y, width, height) in global logical points
+type Bounds = (f32, f32, f32, f32);
+
 /// Display information matching the old screenshots API
 #[derive(Debug, Clone)]
 pub struct DisplayInfo {
@@ -20,12 +37,17 @@ pub struct DisplayInfo {
 pub struct Screen {
     monitor: Monitor,
     pub display_info: DisplayInfo,
+    exclusions: ExclusionConfig,
+    excluded_windows: Mutex<Option<(Instant, Vec<Bounds>)>>, // last lookup
 }
 
 impl Screen {
-    /// Get all available screens
+    /// Get all available screens. The exclusion list is read from the config
+    /// here, so a recording keeps the list it started with, and each screen
+    /// caches where the excluded windows are for the capture session.
     pub fn all() -> Result<Vec<Screen>, String> {
         let monitors = Monitor::all().map_err(|e| e.to_string())?;
+        let exclusions = config::load_config().exclusions;
 
         monitors
             .into_iter()
@@ -50,6 +72,8 @@ impl Screen {
                         scale_factor,
                     },
                     monitor,
+                    exclusions: exclusions.clone(),
+                    excluded_windows: Mutex::default(),
                 })
             })
             .collect()
@@ -94,9 +118,64 @@ impl Screen {
             return Err("Invalid capture area".to_string());
         }
 
-        let cropped = image::imageops::crop_imm(&full, crop_x, crop_y, crop_w, crop_h).to_image();
+        let mut cropped =
+            image::imageops::crop_imm(&full, crop_x, crop_y, crop_w, crop_h).to_image();
+        self.mask_excluded_windows(&mut cropped, crop_x, crop_y);
         Ok(cropped)
     }
+
+    /// Mask windows of excluded apps in a capture whose top-left is at physical
+    /// (`origin_x`, `origin_y`) on this monitor
+    fn mask_excluded_windows(&self, img: &mut RgbaImage, origin_x: u32, origin_y: u32) {
+        if self.exclusions.apps.is_empty() {
+            return;
+        }
+        let scale = self.display_info.scale_factor;
+        // Window bounds are global logical points; redactions are in logical
+        // pixels relative to the captured image
+        let left = self.display_info.x as f32 + origin_x as f32 / scale;
+        let top = self.display_info.y as f32 + origin_y as f32 / scale;
+        let redactions: Vec<Redaction> = self
+            .cached_excluded_windows()
+            .into_iter()
+            .map(|(x, y, width, height)| Redaction {
+                x: x - left,
+                y: y - top,
+                width,
+                height,
+                mode: self.exclusions.mode,
+            })
+            .collect();
+        redact::apply_all(img, &redactions, scale);
+    }
+
+    /// `excluded_windows`, looked up again once the last lookup is older than
+    /// `EXCLUDED_WINDOWS_TTL`
+    fn cached_excluded_windows(&self) -> Vec<Bounds> {
+        let mut cache = self.excluded_windows.lock().unwrap();
+        if let Some((looked_up, windows)) = cache.as_ref() {
+            if looked_up.elapsed() < EXCLUDED_WINDOWS_TTL {
+                return windows.clone();
+            }
+        }
+        let windows = excluded_windows(&self.exclusions.apps);
+        *cache = Some((Instant::now(), windows.clone()));
+        windows
+    }
+}
+
+/// Visible parts of excluded apps' windows
+#[cfg(target_os = "macos")]
+fn excluded_windows(apps: &[String]) -> Vec<Bounds> {
+    crate::window_detect::get_visible_windows_of_apps(apps)
+        .into_iter()
+        .map(|r| (r.x as f32, r.y as f32, r.width as f32, r.height as f32))
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn excluded_windows(_apps: &[String]) -> Vec<Bounds> {
+    Vec::new()
 }
 
 /// Get monitor position (platform-specific)
@@ -2,7 +2,7 @@ use tauri::AppHandle;
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-use crate::config::{self, AppConfig, ShortcutConfig};
+use crate::config::{self, AppConfig, ExclusionConfig, RecordingConfig, ShortcutConfig};
 use crate::shortcuts::register_shortcuts_from_config;
 use crate::state::SharedState;
 use crate::tray::update_tray_menu;
@@ -56,7 +56,13 @@ pub fn remove_shortcut(
 
 #[tauri::command]
 pub fn reset_shortcuts_to_default(app: AppHandle) -> Result<AppConfig, String> {
-    let config = AppConfig::default();
+    // Recording and exclusion settings have their own sections and aren't part of the reset
+    let current = config::load_config();
+    let config = AppConfig {
+        recording: current.recording,
+        exclusions: current.exclusions,
+        ..AppConfig::default()
+    };
     config::save_config(&config)?;
     register_shortcuts_from_config(&app)?;
     update_tray_menu(&app);
@@ -73,6 +79,34 @@ pub fn set_developer_mode(app: AppHandle, enabled: bool) -> Result<AppConfig, St
     Ok(cfg)
 }
 
+#[tauri::command]
+pub fn get_recording_config() -> RecordingConfig {
+    config::load_config().recording
+}
+
+/// Takes effect from the next recording
+#[tauri::command]
+pub fn set_recording_config(recording: RecordingConfig) -> Result<AppConfig, String> {
+    let mut cfg = config::load_config();
+    cfg.recording = recording.sanitized();
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
+#[tauri::command]
+pub fn get_exclusion_config() -> ExclusionConfig {
+    config::load_config().exclusions
+}
+
+/// Takes effect from the next capture (for recordings, the next recording)
+#[tauri::command]
+pub fn set_exclusion_config(exclusions: ExclusionConfig) -> Result<AppConfig, String> {
+    let mut cfg = config::load_config();
+    cfg.exclusions = exclusions.sanitized();
+    config::save_config(&cfg)?;
+    Ok(cfg)
+}
+
 #[tauri::command]
 pub fn pause_shortcuts(app: AppHandle, state: tauri::State<SharedState>) -> Result<(), String> {
     {
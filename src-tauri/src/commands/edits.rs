@@ -0,0 +1,58 @@
+use crate::state::{RecordingSession, SharedState};
+use crate::types::{EditOp, EditState};
+
+fn edit_state(session: &RecordingSession) -> EditState {
+    session.edits.state(&session.frames.snapshot())
+}
+
+/// Save the edits with the session, so crash recovery keeps them
+fn save_edits(session: &RecordingSession) -> EditState {
+    session.frames.save_edits(&session.edits);
+    edit_state(session)
+}
+
+/// Edited timeline of a recording
+#[tauri::command]
+pub fn get_edits(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<EditState, String> {
+    let s = state.lock().unwrap();
+    Ok(edit_state(s.session(&session_id)?))
+}
+
+/// Apply a timeline edit (delete, duplicate, hold, reverse, speed)
+#[tauri::command]
+pub fn apply_edit(
+    state: tauri::State<SharedState>,
+    session_id: String,
+    op: EditOp,
+) -> Result<EditState, String> {
+    println!("[DEBUG][apply_edit] {:?}", op);
+    let mut s = state.lock().unwrap();
+    let session = s.session_mut(&session_id)?;
+    session.edits.apply(op, session.frames.len())?;
+    Ok(save_edits(session))
+}
+
+#[tauri::command]
+pub fn undo_edit(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<EditState, String> {
+    let mut s = state.lock().unwrap();
+    let session = s.session_mut(&session_id)?;
+    session.edits.undo();
+    Ok(save_edits(session))
+}
+
+#[tauri::command]
+pub fn redo_edit(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<EditState, String> {
+    let mut s = state.lock().unwrap();
+    let session = s.session_mut(&session_id)?;
+    session.edits.redo();
+    Ok(save_edits(session))
+}
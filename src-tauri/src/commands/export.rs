@@ -1,50 +1,76 @@
-use std::fs::File;
-use std::path::PathBuf;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use std::thread;
+use std::time::Duration;
 
 use crate::capture::Screen;
 use base64::{engine::general_purpose::STANDARD, Engine};
-use gif::{Encoder, Frame, Repeat};
 use image::RgbaImage;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+use crate::animation;
+use crate::annotate;
+use crate::contact_sheet::ContactSheet;
+use crate::cursor::CursorOverlay;
+use crate::edit_list::Timeline;
+use crate::export_jobs::{CancelToken, ExportJobs, CANCELLED};
+use crate::frame_sequence;
+use crate::frame_source::FrameSource;
+use crate::frame_store::FrameSnapshot;
+use crate::gif_encoder;
+use crate::keystrokes::{self, KeystrokeOverlay};
+use crate::redact;
+use crate::shortcuts::{register_recording_shortcuts, unregister_recording_shortcuts};
 use crate::state::SharedState;
-use crate::types::{ExportConfig, ExportProgress, GifLoopMode, SaveResult, SizeEstimate};
+use crate::tray::create_recording_overlay;
+use crate::types::{
+    Annotation, ContactSheetConfig, CropRect, ExportConfig, ExportFormat, ExportParams,
+    ExportProgress, GifDither, GifLoopMode, GifPalette, Redaction, Region, SaveResult,
+    SizeEstimate,
+};
+use crate::video;
 
 #[tauri::command]
 pub fn estimate_export_size(
     state: tauri::State<SharedState>,
+    session_id: String,
     config: ExportConfig,
-) -> SizeEstimate {
+) -> Result<SizeEstimate, String> {
     let s = state.lock().unwrap();
+    let session = s.session(&session_id)?;
+    let frames = session.frames.snapshot();
+    let timeline = session
+        .edits
+        .timeline(frames.len())
+        .trimmed(config.start_frame, config.end_frame);
+    drop(s);
 
-    let (orig_width, orig_height) = if let Some(frame) = s.frames.first() {
-        frame.dimensions()
+    let (orig_width, orig_height) = if let Some(dimensions) = frames.dimensions() {
+        dimensions
     } else {
-        return SizeEstimate {
+        return Ok(SizeEstimate {
             frame_count: 0,
             output_width: 0,
             output_height: 0,
+            duration_ms: 0,
             estimated_bytes: 0,
             formatted: "0 B".to_string(),
-        };
+        });
     };
 
-    let start = config.start_frame.min(s.frames.len());
-    let end = config.end_frame.min(s.frames.len());
-    let trimmed_count = if end > start { end - start } else { 0 };
-
-    // Output duration = original duration / speed
+    // Output duration = each clip's (wall-clock) duration / its speed
     // Output frames = output duration × target_fps
-    // = (trimmed_count / recording_fps / speed) × target_fps
-    let speed = config.speed.clamp(0.1, 10.0) as f64;
-    let original_duration = trimmed_count as f64 / s.recording_fps as f64;
-    let output_duration = original_duration / speed;
+    let output_duration = timeline.output_ms(&frames) / 1000.0;
     let final_frame_count = (output_duration * config.target_fps as f64).round() as usize;
 
-    let output_width = (orig_width as f32 * config.output_scale) as u32;
-    let output_height = (orig_height as f32 * config.output_scale) as u32;
+    let (crop_width, crop_height) =
+        match config.crop.and_then(|c| c.within(orig_width, orig_height)) {
+            Some(crop) => (crop.width, crop.height),
+            None => (orig_width, orig_height),
+        };
+    let output_width = (crop_width as f32 * config.output_scale) as u32;
+    let output_height = (crop_height as f32 * config.output_scale) as u32;
 
     let total_frames = if config.loop_mode == "pingpong" && final_frame_count > 2 {
         final_frame_count * 2 - 2
@@ -52,21 +78,43 @@ pub fn estimate_export_size(
         final_frame_count
     };
 
-    // Adjust bytes_per_pixel based on quality (1-100)
-    // Low quality (1) -> ~0.05, High quality (100) -> ~0.4 (8x difference)
     let quality_factor = config.quality.clamp(1, 100) as f64 / 100.0;
-    let bytes_per_pixel = 0.05 + quality_factor * 0.35;
+    let bytes_per_pixel = match config.format {
+        // Adjust bytes_per_pixel based on quality (1-100)
+        // Low quality (1) -> ~0.05, High quality (100) -> ~0.4 (8x difference)
+        ExportFormat::Gif => {
+            // A shared palette keeps indices stable between frames, which LZW likes;
+            // dithering noise compresses poorly, error diffusion the worst
+            let palette_factor = match config.palette {
+                GifPalette::PerFrame => 1.0,
+                GifPalette::Global => 0.9,
+            };
+            let dither_factor = match config.dither {
+                GifDither::None => 1.0,
+                GifDither::Ordered => 1.15,
+                GifDither::FloydSteinberg => 1.3,
+            };
+            (0.05 + quality_factor * 0.35) * palette_factor * dither_factor
+        }
+        // Lossless 32-bit deflate; quality only changes compression effort (~10%)
+        ExportFormat::Apng => 0.75 - quality_factor * 0.08,
+        // Lossless VP8L typically lands ~25% below PNG on UI content
+        ExportFormat::Webp => 0.55,
+        // Standalone PNGs: like APNG, but no frame reuse
+        ExportFormat::PngSequence | ExportFormat::Zip => 0.8 - quality_factor * 0.08,
+    };
     let estimated_bytes =
         (total_frames as f64 * output_width as f64 * output_height as f64 * bytes_per_pixel) as u64;
     let formatted = format_bytes(estimated_bytes);
 
-    SizeEstimate {
+    Ok(SizeEstimate {
         frame_count: total_frames,
         output_width,
         output_height,
+        duration_ms: (output_duration * 1000.0).round() as u64,
         estimated_bytes,
         formatted,
-    }
+    })
 }
 
 fn format_bytes(bytes: u64) -> String {
@@ -85,19 +133,32 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// `crop` previews an export crop on the thumbnail
 #[tauri::command]
 pub fn get_frame_thumbnail(
     state: tauri::State<SharedState>,
+    session_id: String,
     frame_index: usize,
     max_height: u32,
+    crop: Option<CropRect>,
 ) -> Result<String, String> {
     let s = state.lock().unwrap();
-
-    if frame_index >= s.frames.len() {
-        return Err("Frame index out of bounds".to_string());
+    let session = s.session(&session_id)?;
+    // `frame_index` is a position in the edited timeline
+    let index = session
+        .edits
+        .timeline(session.frames.len())
+        .source_index(frame_index)
+        .ok_or("Frame index out of bounds")?;
+    let mut frame = session.frames.get(index)?;
+    let annotations = session.frames.annotations().to_vec();
+    let redactions = session.frames.redactions();
+    drop(s);
+    redact::apply_for_frame(&mut frame, &redactions, index);
+    annotate::draw_for_frame(&mut frame, &annotations, index);
+    if let Some(crop) = crop.and_then(|c| c.within(frame.width(), frame.height())) {
+        frame = crop_frame(&frame, crop);
     }
-
-    let frame = &s.frames[frame_index];
     let (orig_w, orig_h) = frame.dimensions();
 
     let scale = max_height as f32 / orig_h as f32;
@@ -105,7 +166,7 @@ pub fn get_frame_thumbnail(
     let thumb_h = max_height;
 
     let thumbnail = image::imageops::resize(
-        frame,
+        &frame,
         thumb_w,
         thumb_h,
         image::imageops::FilterType::Triangle,
@@ -130,40 +191,39 @@ pub fn get_frame_thumbnail(
 #[tauri::command]
 pub fn get_filmstrip(
     state: tauri::State<SharedState>,
+    session_id: String,
     count: usize,
     thumb_height: u32,
 ) -> Result<Vec<String>, String> {
     let s = state.lock().unwrap();
-    let total = s.frames.len();
+    let session = s.session(&session_id)?;
+    let frames = session.frames.snapshot();
+    let timeline = session.edits.timeline(frames.len());
+    let annotations = session.frames.annotations().to_vec();
+    let redactions = session.frames.redactions();
+    drop(s);
+    let total = timeline.len();
 
     if total == 0 {
         return Err("No frames available".to_string());
     }
 
-    let count = count.min(total).max(1);
-    let step = if count > 1 {
-        (total - 1) as f32 / (count - 1) as f32
-    } else {
-        0.0
-    };
-
-    let mut thumbnails = Vec::with_capacity(count);
+    // Thumbnails follow the edited timeline
+    let indices = sample_evenly(0, total, count);
+    let mut thumbnails = Vec::with_capacity(indices.len());
 
-    for i in 0..count {
-        let frame_idx = if count > 1 {
-            ((i as f32 * step).round() as usize).min(total - 1)
-        } else {
-            0
-        };
-
-        let frame = &s.frames[frame_idx];
+    for frame_idx in indices {
+        let source = timeline.source_index(frame_idx).unwrap_or(0);
+        let mut frame = frames.get(source)?;
+        redact::apply_for_frame(&mut frame, &redactions, source);
+        annotate::draw_for_frame(&mut frame, &annotations, source);
         let (orig_w, orig_h) = frame.dimensions();
 
         let scale = thumb_height as f32 / orig_h as f32;
         let thumb_w = (orig_w as f32 * scale) as u32;
 
         let thumbnail = image::imageops::resize(
-            frame,
+            &frame,
             thumb_w,
             thumb_height,
             image::imageops::FilterType::Nearest,
@@ -183,21 +243,159 @@ pub fn get_filmstrip(
     Ok(thumbnails)
 }
 
+/// `count` frame indices spread evenly over `start..end`, including both ends
+fn sample_evenly(start: usize, end: usize, count: usize) -> Vec<usize> {
+    let total = end.saturating_sub(start);
+    if total == 0 {
+        return Vec::new();
+    }
+    let count = count.min(total).max(1);
+    if count == 1 {
+        return vec![start];
+    }
+    let step = (total - 1) as f32 / (count - 1) as f32;
+    (0..count)
+        .map(|i| start + ((i as f32 * step).round() as usize).min(total - 1))
+        .collect()
+}
+
+#[tauri::command]
+pub fn export_contact_sheet(
+    app: AppHandle,
+    state: tauri::State<SharedState>,
+    jobs: tauri::State<ExportJobs>,
+    session_id: String,
+    config: ContactSheetConfig,
+) -> Result<u64, String> {
+    let s = state.lock().unwrap();
+    let session = s.session(&session_id)?;
+    let frames = session.frames.snapshot();
+    let timeline = session.edits.timeline(frames.len());
+    drop(s);
+    let Some((width, height)) = frames.dimensions() else {
+        return Err("No frames to export".to_string());
+    };
+    let end = config.end_frame.min(timeline.len());
+    let indices = sample_evenly(config.start_frame, end, config.frame_count);
+    if indices.is_empty() {
+        return Err("Invalid frame range".to_string());
+    }
+
+    let output = resolve_output_path(config.output_path.as_deref(), "contact_sheet", "png")?;
+
+    let path = output.path.clone();
+    submit_export_job(&app, &jobs, "export_contact_sheet", output, move |job| {
+        frames.burn_redactions()?;
+        // Tiles show the same frames as the animated exports
+        let annotations = frames.annotations();
+        let mut sheet = ContactSheet::new(width, height, indices.len(), &config);
+        for (i, &index) in indices.iter().enumerate() {
+            let source = timeline.source_index(index).unwrap_or(0);
+            let mut frame = frames.get(source)?;
+            annotate::draw_for_frame(&mut frame, annotations, source);
+            sheet.add(&frame, index, frames.timestamp_ms(source));
+            job.progress(i + 1, indices.len(), "rendering")?;
+        }
+        sheet.save(&path)?;
+        Ok(None)
+    })
+}
+
 #[tauri::command]
 pub fn save_screenshot(
     app: AppHandle,
     state: tauri::State<SharedState>,
     scale: Option<f32>,
+    annotations: Option<Vec<Annotation>>,
+    redactions: Option<Vec<Redaction>>,
 ) -> Result<String, String> {
     println!("[DEBUG][save_screenshot] ====== 被调用 ======");
-    let s = state.lock().unwrap();
-    let region = s.region.clone().ok_or("No region selected")?;
+    let region = state
+        .lock()
+        .unwrap()
+        .region
+        .clone()
+        .ok_or("No region selected")?;
+    save_screenshot_internal(&app, &region, scale, annotations, redactions)
+}
+
+/// Delay preselected by the timed screenshot shortcut, and the longest accepted
+pub const DEFAULT_SCREENSHOT_DELAY_SECS: u32 = 5;
+const MAX_SCREENSHOT_DELAY_SECS: u32 = 60;
+
+/// Take the screenshot after `delay_secs`, so hover states, open menus and
+/// tooltips can be set up first. The region overlay counts down meanwhile; any
+/// registered shortcut (e.g. ESC) cancels.
+#[tauri::command]
+pub fn start_timed_screenshot(
+    app: AppHandle,
+    state: tauri::State<SharedState>,
+    delay_secs: u32,
+    scale: Option<f32>,
+    annotations: Option<Vec<Annotation>>,
+    redactions: Option<Vec<Redaction>>,
+) -> Result<(), String> {
+    let delay_secs = delay_secs.clamp(1, MAX_SCREENSHOT_DELAY_SECS);
+    let region = {
+        let mut s = state.lock().unwrap();
+        if s.capture_countdown {
+            return Err("A timed capture is already counting down".to_string());
+        }
+        let region = s.region.clone().ok_or("No region selected")?;
+        s.capture_countdown = true;
+        region
+    };
+    println!("[DEBUG][start_timed_screenshot] {} 秒后截图", delay_secs);
+
+    register_recording_shortcuts(&app);
+    create_recording_overlay(&app, &region, true, Some(delay_secs));
+
+    let state = state.inner().clone();
+    thread::spawn(move || {
+        let counting = || state.lock().unwrap().capture_countdown;
+        let mut cancelled = false;
+        'countdown: for remaining in (1..=delay_secs).rev() {
+            let _ = app.emit("capture-countdown", remaining);
+            // Check often so a cancel takes effect right away
+            for _ in 0..10 {
+                thread::sleep(Duration::from_millis(100));
+                if !counting() {
+                    cancelled = true;
+                    break 'countdown;
+                }
+            }
+        }
+        state.lock().unwrap().capture_countdown = false;
+        unregister_recording_shortcuts(&app);
+        if let Some(overlay) = app.get_webview_window("recording-overlay") {
+            let _ = overlay.close();
+        }
+        if cancelled {
+            println!("[DEBUG][start_timed_screenshot] 已取消");
+            return;
+        }
+
+        // Let the overlay disappear before capturing
+        thread::sleep(Duration::from_millis(150));
+        if let Err(e) = save_screenshot_internal(&app, &region, scale, annotations, redactions) {
+            println!("[DEBUG][start_timed_screenshot] 截图失败: {}", e);
+        }
+    });
+    Ok(())
+}
+
+fn save_screenshot_internal(
+    app: &AppHandle,
+    region: &Region,
+    scale: Option<f32>,
+    annotations: Option<Vec<Annotation>>,
+    redactions: Option<Vec<Redaction>>,
+) -> Result<String, String> {
     let output_scale = scale.unwrap_or(1.0).clamp(0.1, 1.0);
     println!(
         "[DEBUG][save_screenshot] region: x={}, y={}, w={}, h={}, scale={}",
         region.x, region.y, region.width, region.height, output_scale
     );
-    drop(s);
 
     let screens = Screen::all().map_err(|e| {
         println!("[DEBUG][save_screenshot] Screen::all 错误: {}", e);
@@ -226,10 +424,22 @@ pub fn save_screenshot(
         captured.height()
     );
 
-    let captured_rgba =
+    let mut captured_rgba =
         RgbaImage::from_raw(captured.width(), captured.height(), captured.into_raw())
             .ok_or("Failed to convert image")?;
 
+    // Redactions and annotations are drawn in selection pixels; burn them in
+    // before scaling. Redact first so annotations stay readable on top.
+    let scale = captured_rgba.width() as f32 / region.width.max(1) as f32;
+    if let Some(redactions) = redactions.filter(|r| !r.is_empty()) {
+        println!("[DEBUG][save_screenshot] 打码 {} 个区域", redactions.len());
+        redact::apply_all(&mut captured_rgba, &redactions, scale);
+    }
+    if let Some(annotations) = annotations.filter(|a| !a.is_empty()) {
+        println!("[DEBUG][save_screenshot] 绘制 {} 个标注", annotations.len());
+        annotate::render(&mut captured_rgba, &annotations, scale);
+    }
+
     let img = if (output_scale - 1.0).abs() > 0.01 {
         let new_w = (captured_rgba.width() as f32 * output_scale) as u32;
         let new_h = (captured_rgba.height() as f32 * output_scale) as u32;
@@ -280,9 +490,15 @@ pub fn save_screenshot(
 pub fn export_gif(
     app: AppHandle,
     state: tauri::State<SharedState>,
-    config: ExportConfig,
-) -> Result<(), String> {
+    jobs: tauri::State<ExportJobs>,
+    session_id: String,
+    mut config: ExportConfig,
+) -> Result<u64, String> {
     println!("[DEBUG][export_gif] ====== 被调用 ======");
+    config.keystrokes.font_size = config
+        .keystrokes
+        .font_size
+        .clamp(keystrokes::MIN_FONT_SIZE, keystrokes::MAX_FONT_SIZE);
     println!(
         "[DEBUG][export_gif] config: start={}, end={}, scale={}, fps={}, loop={}",
         config.start_frame,
@@ -292,248 +508,626 @@ pub fn export_gif(
         config.loop_mode
     );
 
-    let mut s = state.lock().unwrap();
+    let s = state.lock().unwrap();
+    let session = s.session(&session_id)?;
+    let frames = session.frames.snapshot();
+    let timeline = session.edits.timeline(frames.len());
+    drop(s);
 
-    if s.frames.is_empty() {
+    if frames.is_empty() {
         println!("[DEBUG][export_gif] 错误: 没有帧可保存");
-        let _ = app.emit(
-            "export-complete",
-            SaveResult {
-                success: false,
-                path: None,
-                error: Some("No frames to export".to_string()),
-            },
-        );
-        return Ok(());
+        return Err("No frames to export".to_string());
     }
 
-    let total_frames = s.frames.len();
-    let recording_fps = s.recording_fps;
     println!(
-        "[DEBUG][export_gif] 原始帧数: {}, 录制帧率: {}",
-        total_frames, recording_fps
+        "[DEBUG][export_gif] 原始帧数: {}, 时长: {}ms",
+        frames.len(),
+        frames.duration_ms()
     );
 
-    let all_frames = s.frames.clone();
-    drop(s);
+    let ext = match config.format {
+        ExportFormat::Gif => "gif",
+        ExportFormat::Apng => "png",
+        ExportFormat::Webp => "webp",
+        ExportFormat::PngSequence => "", // a folder
+        ExportFormat::Zip => "zip",
+    };
+    let output = resolve_output_path(config.output_path.as_deref(), "recording", ext)?;
+    println!("[DEBUG][export_gif] 保存路径: {:?}", output.path);
+
+    let path = output.path.clone();
+    submit_export_job(&app, &jobs, "export_gif", output, move |job| {
+        frames.burn_redactions()?;
+        // A folder has no single file size to fit
+        let max_bytes = config
+            .max_bytes
+            .filter(|_| config.format != ExportFormat::PngSequence);
+        let params = match max_bytes {
+            Some(max_bytes) => export_to_fit(job, &frames, &timeline, &config, max_bytes, &path)?,
+            None => {
+                let final_frames = prepare_frames(&frames, &timeline, &config, job.cancel)?;
+                encode_animation(&final_frames, &config, &path, |current, total| {
+                    job.progress(current, total, "encoding")
+                })?;
+                ExportParams {
+                    quality: config.quality,
+                    output_scale: config.output_scale,
+                    target_fps: config.target_fps,
+                    bytes: file_size(&path),
+                    attempts: 1,
+                }
+            }
+        };
+        frames.mark_exported();
+        Ok(Some(params))
+    })
+}
 
-    let config = config.clone();
+/// What a running export job needs to report progress and notice cancellation
+struct JobContext<'a> {
+    app: &'a AppHandle,
+    job_id: u64,
+    cancel: &'a CancelToken,
+}
 
-    thread::spawn(move || {
-        let start = config.start_frame.min(total_frames);
-        let end = config.end_frame.min(total_frames);
-        if end <= start {
-            let _ = app.emit(
-                "export-complete",
-                SaveResult {
-                    success: false,
-                    path: None,
-                    error: Some("Invalid frame range".to_string()),
-                },
-            );
-            return;
-        }
-        let trimmed_frames: Vec<_> = all_frames[start..end].to_vec();
-        let trimmed_count = trimmed_frames.len();
-        println!("[DEBUG][export_gif] 裁剪后帧数: {}", trimmed_count);
-
-        // Calculate target frame count based on output duration and fps
-        // output_duration = original_duration / speed
-        // output_frames = output_duration × target_fps
-        let speed = config.speed.clamp(0.1, 10.0);
-        let original_duration = trimmed_count as f32 / recording_fps as f32;
-        let output_duration = original_duration / speed;
-        let target_frame_count = (output_duration * config.target_fps as f32).round() as usize;
-        let target_frame_count = target_frame_count.max(1);
-
-        // Sample frames uniformly
-        let sampled_frames: Vec<_> = if target_frame_count >= trimmed_count {
-            trimmed_frames
-        } else {
-            (0..target_frame_count)
-                .map(|i| {
-                    let src_idx = (i as f32 * (trimmed_count - 1) as f32
-                        / (target_frame_count - 1).max(1) as f32)
-                        .round() as usize;
-                    trimmed_frames[src_idx.min(trimmed_count - 1)].clone()
-                })
-                .collect()
-        };
-        println!(
-            "[DEBUG][export_gif] 采样后: target={}, 实际={}, speed={}",
-            target_frame_count,
-            sampled_frames.len(),
-            speed
+impl JobContext<'_> {
+    /// Emit "export-progress" for this job; errors once the job is cancelled,
+    /// which aborts the encoder calling it
+    fn progress(&self, current: usize, total: usize, stage: &str) -> Result<(), String> {
+        self.cancel.check()?;
+        let _ = self.app.emit(
+            "export-progress",
+            ExportProgress {
+                job_id: self.job_id,
+                current,
+                total,
+                stage: stage.to_string(),
+            },
         );
+        Ok(())
+    }
+}
 
-        if sampled_frames.is_empty() {
-            let _ = app.emit(
-                "export-complete",
-                SaveResult {
-                    success: false,
-                    path: None,
-                    error: Some("No frames after sampling".to_string()),
-                },
-            );
-            return;
-        }
-
-        let output_scale = config.output_scale.clamp(0.1, 1.0);
-        let scaled_frames: Vec<RgbaImage> = if (output_scale - 1.0).abs() > 0.01 {
-            println!("[DEBUG][export_gif] 缩放帧: scale={}", output_scale);
-            sampled_frames
-                .into_iter()
-                .map(|f| {
-                    let new_w = (f.width() as f32 * output_scale) as u32;
-                    let new_h = (f.height() as f32 * output_scale) as u32;
-                    image::imageops::resize(&f, new_w, new_h, image::imageops::FilterType::Triangle)
-                })
-                .collect()
-        } else {
-            sampled_frames
+/// Run an export on the job queue. `run` does the work and returns the
+/// parameters used; this reports the outcome via "export-complete" tagged with
+/// the job ID (also if `run` panics). If the job is cancelled or fails, a file
+/// it created is deleted again.
+fn submit_export_job<F>(
+    app: &AppHandle,
+    jobs: &ExportJobs,
+    tag: &'static str,
+    output: ExportOutput,
+    run: F,
+) -> Result<u64, String>
+where
+    F: FnOnce(&JobContext) -> Result<Option<ExportParams>, String> + Send + 'static,
+{
+    let app = app.clone();
+    jobs.submit(move |job_id, cancel| {
+        let job = JobContext {
+            app: &app,
+            job_id,
+            cancel: &cancel,
         };
-
-        let gif_loop_mode = match config.loop_mode.as_str() {
-            "once" => GifLoopMode::Once,
-            "pingpong" => GifLoopMode::PingPong,
-            _ => GifLoopMode::Infinite,
+        let result = if !cancel.is_cancelled() {
+            // A panicking encoder still has to report, or the editor waits forever
+            panic::catch_unwind(AssertUnwindSafe(|| run(&job)))
+                .unwrap_or_else(|_| Err("Export failed unexpectedly".to_string()))
+        } else {
+            Err(CANCELLED.to_string())
         };
 
-        let final_frames: Vec<RgbaImage> = match gif_loop_mode {
-            GifLoopMode::PingPong if scaled_frames.len() > 2 => {
-                let mut result = scaled_frames.clone();
-                let reversed: Vec<_> = scaled_frames[1..scaled_frames.len() - 1]
-                    .iter()
-                    .rev()
-                    .cloned()
-                    .collect();
-                result.extend(reversed);
+        let save_result = match result {
+            Ok(export_params) => {
+                let path = output.path.to_string_lossy().to_string();
                 println!(
-                    "[DEBUG][export_gif] PingPong 模式: {} -> {} 帧",
-                    scaled_frames.len(),
-                    result.len()
+                    "[DEBUG][{}] ====== 完成 #{} ====== 路径: {}",
+                    tag, job_id, path
                 );
-                result
+                SaveResult {
+                    success: true,
+                    path: Some(path),
+                    error: None,
+                    export_params,
+                    job_id: Some(job_id),
+                }
             }
-            _ => scaled_frames,
-        };
-
-        let output_dir = dirs::picture_dir()
-            .or_else(|| dirs::home_dir())
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("lovshot");
-
-        if let Err(e) = std::fs::create_dir_all(&output_dir) {
-            let _ = app.emit(
-                "export-complete",
+            Err(e) => {
+                // Never delete what was there before, e.g. a file being overwritten
+                if output.created {
+                    if output.path.is_dir() {
+                        let _ = std::fs::remove_dir_all(&output.path);
+                    } else {
+                        let _ = std::fs::remove_file(&output.path);
+                    }
+                }
+                println!("[DEBUG][{}] ====== 错误 #{} ====== {}", tag, job_id, e);
                 SaveResult {
                     success: false,
                     path: None,
-                    error: Some(e.to_string()),
-                },
-            );
-            return;
+                    error: Some(e),
+                    export_params: None,
+                    job_id: Some(job_id),
+                }
+            }
+        };
+        let _ = app.emit("export-complete", save_result);
+    })
+}
+
+#[tauri::command]
+pub fn cancel_export(jobs: tauri::State<ExportJobs>, job_id: u64) -> Result<(), String> {
+    if jobs.cancel(job_id) {
+        Ok(())
+    } else {
+        Err("Export job not found".to_string())
+    }
+}
+
+/// Encode already prepared frames in the configured output format
+fn encode_animation<F>(
+    frames: &PreparedFrames,
+    config: &ExportConfig,
+    path: &Path,
+    on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    let gif_loop_mode = match config.loop_mode.as_str() {
+        "once" => GifLoopMode::Once,
+        "pingpong" => GifLoopMode::PingPong,
+        _ => GifLoopMode::Infinite,
+    };
+
+    // APNG/WebP: 0 plays = loop forever
+    let plays = match gif_loop_mode {
+        GifLoopMode::Once => 1,
+        _ => 0,
+    };
+
+    let (width, height) = frames.dimensions();
+    println!(
+        "[DEBUG][export_gif] 开始编码: {:?} {}x{}, {} 帧, 质量 {}",
+        config.format,
+        width,
+        height,
+        frames.len(),
+        config.quality
+    );
+    let delays_ms = &frames.delays_ms;
+
+    match config.format {
+        ExportFormat::Gif => {
+            gif_encoder::encode_gif(frames, delays_ms, config, &gif_loop_mode, path, on_progress)
+        }
+        ExportFormat::Apng => {
+            animation::encode_apng(frames, delays_ms, plays, config.quality, path, on_progress)
+        }
+        ExportFormat::Webp => {
+            animation::encode_webp(frames, delays_ms, plays as u16, path, on_progress)
+        }
+        ExportFormat::PngSequence => {
+            frame_sequence::write_png_sequence(frames, delays_ms, config.quality, path, on_progress)
+        }
+        ExportFormat::Zip => {
+            frame_sequence::write_png_zip(frames, delays_ms, config.quality, path, on_progress)
         }
+    }
+}
 
-        // Use custom path or default
-        let filename = if let Some(ref custom_path) = config.output_path {
-            PathBuf::from(custom_path)
-        } else {
-            let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-            output_dir.join(format!("recording_{}.gif", timestamp))
-        };
-        println!("[DEBUG][export_gif] 保存路径: {:?}", filename);
+/// Lowest values the fit-to-size search will go down to
+const FIT_MIN_QUALITY: u32 = 20;
+const FIT_MIN_SCALE: f32 = 0.2;
+const FIT_MIN_FPS: u32 = 5;
+const FIT_MAX_ATTEMPTS: u32 = 12;
+
+/// Re-encode with progressively cheaper parameters until the file fits in `max_bytes`.
+/// Priority: quality first (least visible), then output scale, then fps.
+fn export_to_fit(
+    job: &JobContext,
+    frames: &FrameSnapshot,
+    timeline: &Timeline,
+    config: &ExportConfig,
+    max_bytes: u64,
+    path: &Path,
+) -> Result<ExportParams, String> {
+    let mut attempt_config = config.clone();
+
+    for attempt in 1..=FIT_MAX_ATTEMPTS {
+        println!(
+            "[DEBUG][export_gif] 尝试 {}: 质量 {}, 缩放 {:.2}, {} fps",
+            attempt, attempt_config.quality, attempt_config.output_scale, attempt_config.target_fps
+        );
 
-        let (width, height) = final_frames[0].dimensions();
-        let frame_count = final_frames.len();
+        let final_frames = prepare_frames(frames, timeline, &attempt_config, job.cancel)?;
+        let stage = format!("attempt {}", attempt);
+        encode_animation(&final_frames, &attempt_config, path, |current, total| {
+            job.progress(current, total, &stage)
+        })?;
+
+        let bytes = file_size(path);
         println!(
-            "[DEBUG][export_gif] 开始编码: {}x{}, {} 帧",
-            width, height, frame_count
+            "[DEBUG][export_gif] 尝试 {} 结果: {} (目标 {})",
+            attempt,
+            format_bytes(bytes),
+            format_bytes(max_bytes)
         );
+        if bytes <= max_bytes {
+            return Ok(ExportParams {
+                quality: attempt_config.quality,
+                output_scale: attempt_config.output_scale,
+                target_fps: attempt_config.target_fps,
+                bytes,
+                attempts: attempt,
+            });
+        }
 
-        let result = (|| -> Result<String, String> {
-            let mut file = File::create(&filename).map_err(|e| e.to_string())?;
-            let mut encoder = Encoder::new(&mut file, width as u16, height as u16, &[])
-                .map_err(|e| e.to_string())?;
+        match next_fit_config(&attempt_config, bytes, max_bytes) {
+            Some(next) => attempt_config = next,
+            None => break,
+        }
+    }
 
-            let repeat = match gif_loop_mode {
-                GifLoopMode::Once => Repeat::Finite(0),
-                _ => Repeat::Infinite,
-            };
-            encoder.set_repeat(repeat).map_err(|e| e.to_string())?;
-
-            // GIF delay is in 1/100 seconds: delay = 100 / fps
-            // (speed already affects frame count, so delay is just based on fps)
-            let delay = if config.target_fps > 0 {
-                (100.0 / config.target_fps as f32).max(1.0) as u16
-            } else {
-                10
-            };
+    let _ = std::fs::remove_file(path);
+    Err(format!(
+        "Could not fit the export under {}",
+        format_bytes(max_bytes)
+    ))
+}
 
-            for (i, rgba_img) in final_frames.into_iter().enumerate() {
-                let mut pixels: Vec<u8> = Vec::with_capacity((width * height * 4) as usize);
-                for pixel in rgba_img.pixels() {
-                    pixels.push(pixel[0]);
-                    pixels.push(pixel[1]);
-                    pixels.push(pixel[2]);
-                    pixels.push(pixel[3]);
-                }
+/// Next, cheaper parameter set for the fit-to-size search, or None when exhausted
+fn next_fit_config(config: &ExportConfig, bytes: u64, max_bytes: u64) -> Option<ExportConfig> {
+    let mut next = config.clone();
+    let ratio = max_bytes as f64 / bytes.max(1) as f64;
 
-                // Map quality (1-100) to gif speed (30-1): higher quality = lower speed = better but slower
-                let gif_speed = 30 - ((config.quality.clamp(1, 100) - 1) * 29 / 99);
-                let mut frame = Frame::from_rgba_speed(
-                    width as u16,
-                    height as u16,
-                    &mut pixels,
-                    gif_speed as i32,
-                );
-                frame.delay = delay;
-                encoder.write_frame(&frame).map_err(|e| e.to_string())?;
-
-                let _ = app.emit(
-                    "export-progress",
-                    ExportProgress {
-                        current: i + 1,
-                        total: frame_count,
-                        stage: "encoding".to_string(),
-                    },
-                );
+    // Only GIF quality trades size; for the lossless formats it is just deflate effort
+    if config.format == ExportFormat::Gif && config.quality > FIT_MIN_QUALITY {
+        next.quality = config.quality.saturating_sub(20).max(FIT_MIN_QUALITY);
+        return Some(next);
+    }
 
-                if i == 0 || (i + 1) % 10 == 0 || i + 1 == frame_count {
-                    println!("[DEBUG][export_gif] 编码帧 {}/{}", i + 1, frame_count);
-                }
-            }
+    // Size grows with pixel area: scale by sqrt(ratio), with some headroom
+    if config.output_scale > FIT_MIN_SCALE {
+        let factor = (ratio.sqrt() * 0.95).min(0.9) as f32;
+        next.output_scale = (config.output_scale * factor).max(FIT_MIN_SCALE);
+        return Some(next);
+    }
 
-            Ok(filename.to_string_lossy().to_string())
-        })();
-
-        match result {
-            Ok(path) => {
-                println!("[DEBUG][export_gif] ====== 完成 ====== 路径: {}", path);
-                let _ = app.emit(
-                    "export-complete",
-                    SaveResult {
-                        success: true,
-                        path: Some(path),
-                        error: None,
-                    },
-                );
+    // Size grows linearly with frame count
+    if config.target_fps > FIT_MIN_FPS {
+        let factor = (ratio * 0.95).min(0.85);
+        next.target_fps = ((config.target_fps as f64 * factor) as u32).max(FIT_MIN_FPS);
+        return Some(next);
+    }
+
+    None
+}
+
+/// Size of a file, or the total size of the files in a folder
+fn file_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        return std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.metadata().ok())
+                    .map(|m| m.len())
+                    .sum()
+            })
+            .unwrap_or(0);
+    }
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+#[tauri::command]
+pub fn export_video(
+    app: AppHandle,
+    state: tauri::State<SharedState>,
+    jobs: tauri::State<ExportJobs>,
+    session_id: String,
+    mut config: ExportConfig,
+) -> Result<u64, String> {
+    println!("[DEBUG][export_video] ====== 被调用 ======");
+    config.keystrokes.font_size = config
+        .keystrokes
+        .font_size
+        .clamp(keystrokes::MIN_FONT_SIZE, keystrokes::MAX_FONT_SIZE);
+    println!(
+        "[DEBUG][export_video] config: start={}, end={}, scale={}, fps={}",
+        config.start_frame, config.end_frame, config.output_scale, config.target_fps
+    );
+
+    let s = state.lock().unwrap();
+    let session = s.session(&session_id)?;
+    let frames = session.frames.snapshot();
+    let timeline = session.edits.timeline(frames.len());
+    drop(s);
+
+    if frames.is_empty() {
+        println!("[DEBUG][export_video] 错误: 没有帧可保存");
+        return Err("No frames to export".to_string());
+    }
+
+    let output = resolve_output_path(config.output_path.as_deref(), "recording", "webm")?;
+    println!("[DEBUG][export_video] 保存路径: {:?}", output.path);
+
+    let path = output.path.clone();
+    submit_export_job(&app, &jobs, "export_video", output, move |job| {
+        frames.burn_redactions()?;
+        let final_frames = prepare_frames(&frames, &timeline, &config, job.cancel)?;
+
+        let (width, height) = final_frames.dimensions();
+        println!(
+            "[DEBUG][export_video] 开始编码: {}x{}, {} 帧",
+            width,
+            height,
+            final_frames.len()
+        );
+
+        video::encode_webm(
+            &final_frames,
+            &final_frames.delays_ms,
+            config.target_fps,
+            config.quality,
+            &path,
+            |current, total| job.progress(current, total, "encoding"),
+        )?;
+        frames.mark_exported();
+
+        Ok(None)
+    })
+}
+
+/// Frames ready to encode, each with how long it stays on screen. Only the
+/// sampling is worked out up front; each frame is loaded and rendered when an
+/// encoder asks for it (see `FrameSource`).
+struct PreparedFrames<'a> {
+    frames: &'a FrameSnapshot,
+    cursor: Option<CursorOverlay<'a>>,
+    keystrokes: Option<KeystrokeOverlay<'a>>,
+    crop: Option<CropRect>,
+    size: (u32, u32),
+    needs_scale: bool,
+    sampled: Vec<(usize, u64)>, // source frame and source time per output frame
+    delays_ms: Vec<u32>,        // per output frame, including the ping-pong return
+    cancel: &'a CancelToken,
+}
+
+/// Trim, resample, scale and loop-expand recorded frames per the edits and the
+/// export config. Shared by every export format so they all see the same frame
+/// sequence. Sampling follows the frames' real capture times, scaled by each
+/// clip's speed: each output tick at `target_fps` shows the frame on screen at
+/// that moment, and ticks that land on the same frame (capture fell behind, or
+/// a hold) become one frame with a longer delay.
+/// Nothing is loaded here: frames are read from the frame store, cropped and
+/// scaled one at a time as they are encoded, to keep peak memory low.
+/// Redactions must already be burned in (`FrameSnapshot::burn_redactions`).
+fn prepare_frames<'a>(
+    frames: &'a FrameSnapshot,
+    timeline: &Timeline,
+    config: &'a ExportConfig,
+    cancel: &'a CancelToken,
+) -> Result<PreparedFrames<'a>, String> {
+    // The range is in edited frames
+    let timeline = timeline.trimmed(config.start_frame, config.end_frame);
+    if timeline.is_empty() {
+        return Err("Invalid frame range".to_string());
+    }
+    println!(
+        "[DEBUG][prepare_frames] 裁剪后帧数: {}, 片段数: {}",
+        timeline.len(),
+        timeline.clips().len()
+    );
+
+    // Output frames = output duration × target_fps
+    let output_ms = timeline.output_ms(frames);
+    let tick_ms = 1000.0 / config.target_fps.max(1) as f64;
+    let tick_count = ((output_ms / tick_ms).round() as usize).max(1);
+
+    let cursor = CursorOverlay::new(frames, config.show_cursor, config.highlight_clicks);
+    let keystrokes = KeystrokeOverlay::new(frames.keystrokes(), &config.keystrokes);
+    // Source time runs backwards in reversed clips and stands still in holds
+    let overlay_changes = |a_ms: u64, b_ms: u64| {
+        let (from_ms, to_ms) = (a_ms.min(b_ms), a_ms.max(b_ms));
+        from_ms != to_ms
+            && (cursor
+                .as_ref()
+                .is_some_and(|c| c.changes_between(from_ms, to_ms))
+                || keystrokes
+                    .as_ref()
+                    .is_some_and(|k| k.changes_between(from_ms, to_ms)))
+    };
+
+    // (source frame, source time) and delay per output frame; delays come from
+    // rounding each tick's end time so they add up to the exact output duration.
+    // Ticks on the same frame aren't merged while an overlay is changing.
+    let mut sampled: Vec<(usize, u64)> = Vec::new();
+    let mut delays_ms: Vec<u32> = Vec::new();
+    let mut tick_end_ms = 0u64;
+    for tick in 0..tick_count {
+        let Some((index, source_ms)) = timeline.source_at(frames, tick as f64 * tick_ms) else {
+            break;
+        };
+        let next_end_ms = ((tick + 1) as f64 * tick_ms).round() as u64;
+        let delay = (next_end_ms - tick_end_ms) as u32;
+        tick_end_ms = next_end_ms;
+        match (sampled.last(), delays_ms.last_mut()) {
+            (Some(&(last, last_ms)), Some(last_delay))
+                if last == index && !overlay_changes(last_ms, source_ms) =>
+            {
+                *last_delay += delay
             }
-            Err(e) => {
-                println!("[DEBUG][export_gif] ====== 错误 ====== {}", e);
-                let _ = app.emit(
-                    "export-complete",
-                    SaveResult {
-                        success: false,
-                        path: None,
-                        error: Some(e),
-                    },
-                );
+            _ => {
+                sampled.push((index, source_ms));
+                delays_ms.push(delay);
             }
         }
-    });
+    }
+    println!(
+        "[DEBUG][prepare_frames] 采样后: {} 个时间点 -> {} 帧",
+        tick_count,
+        sampled.len()
+    );
 
-    Ok(())
+    let Some((width, height)) = frames.dimensions().filter(|_| !sampled.is_empty()) else {
+        return Err("No frames after sampling".to_string());
+    };
+
+    let crop = config.crop.and_then(|crop| crop.within(width, height));
+    if let Some(crop) = crop {
+        println!(
+            "[DEBUG][prepare_frames] 裁剪区域: {}x{} @ ({}, {})",
+            crop.width, crop.height, crop.x, crop.y
+        );
+    }
+    let (crop_w, crop_h) = crop.map_or((width, height), |c| (c.width, c.height));
+
+    let output_scale = config.output_scale.clamp(0.1, 1.0);
+    let needs_scale = (output_scale - 1.0).abs() > 0.01;
+    let size = if needs_scale {
+        println!("[DEBUG][prepare_frames] 缩放帧: scale={}", output_scale);
+        (
+            (crop_w as f32 * output_scale) as u32,
+            (crop_h as f32 * output_scale) as u32,
+        )
+    } else {
+        (crop_w, crop_h)
+    };
+
+    // Ping-pong plays the middle frames again backwards; `frame` maps those
+    // output indices back onto `sampled`
+    if config.loop_mode == "pingpong" && sampled.len() > 2 {
+        let middle = 1..sampled.len() - 1;
+        let reversed_delays: Vec<u32> = delays_ms[middle].iter().rev().copied().collect();
+        println!(
+            "[DEBUG][prepare_frames] PingPong 模式: {} -> {} 帧",
+            sampled.len(),
+            sampled.len() + reversed_delays.len()
+        );
+        delays_ms.extend(reversed_delays);
+    }
+
+    Ok(PreparedFrames {
+        frames,
+        cursor,
+        keystrokes,
+        crop,
+        size,
+        needs_scale,
+        sampled,
+        delays_ms,
+        cancel,
+    })
+}
+
+impl FrameSource for PreparedFrames<'_> {
+    fn len(&self) -> usize {
+        self.delays_ms.len()
+    }
+
+    fn dimensions(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn frame(&self, index: usize) -> Result<RgbaImage, String> {
+        self.cancel.check()?;
+        if index >= self.len() {
+            return Err("Frame index out of bounds".to_string());
+        }
+        let sample = match self.sampled.len() {
+            n if index < n => index,
+            n => 2 * n - 2 - index,
+        };
+        let (idx, source_ms) = self.sampled[sample];
+
+        let mut f = self.frames.get(idx)?;
+        annotate::draw_for_frame(&mut f, self.frames.annotations(), idx);
+        if let Some(cursor) = &self.cursor {
+            cursor.apply(&mut f, idx, source_ms);
+        }
+        // Crop after the cursor (recorded in full-frame pixels), before the
+        // badge so it's placed inside the cropped frame
+        if let Some(crop) = self.crop {
+            f = crop_frame(&f, crop);
+        }
+        if let Some(keystrokes) = &self.keystrokes {
+            keystrokes.apply(&mut f, source_ms);
+        }
+        if self.needs_scale {
+            let (width, height) = self.size;
+            f = image::imageops::resize(&f, width, height, image::imageops::FilterType::Triangle);
+        }
+        Ok(f)
+    }
+}
+
+fn crop_frame(frame: &RgbaImage, crop: CropRect) -> RgbaImage {
+    image::imageops::crop_imm(frame, crop.x, crop.y, crop.width, crop.height).to_image()
+}
+
+/// Where an export writes. `created` is set when the export made the file (or
+/// folder) itself; only then is it deleted if the export is cancelled or fails.
+struct ExportOutput {
+    path: PathBuf,
+    created: bool,
+}
+
+/// Resolve the export path: the custom path from the save dialog, or a timestamped
+/// file in the default output directory. Default paths are reserved right away
+/// (with a counter suffix if taken), so exports finishing in the same second
+/// never share a file. An empty `ext` reserves a folder.
+fn resolve_output_path(
+    output_path: Option<&str>,
+    prefix: &str,
+    ext: &str,
+) -> Result<ExportOutput, String> {
+    if let Some(custom_path) = output_path {
+        let path = PathBuf::from(custom_path);
+        return Ok(ExportOutput {
+            created: !path.exists(),
+            path,
+        });
+    }
+
+    let output_dir = dirs::picture_dir()
+        .or_else(|| dirs::home_dir())
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("lovshot");
+    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    for n in 1.. {
+        let stem = match n {
+            1 => format!("{}_{}", prefix, timestamp),
+            _ => format!("{}_{}_{}", prefix, timestamp, n),
+        };
+        let path = if ext.is_empty() {
+            output_dir.join(stem)
+        } else {
+            output_dir.join(format!("{}.{}", stem, ext))
+        };
+        let reserved = if ext.is_empty() {
+            std::fs::create_dir(&path)
+        } else {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .map(|_| ())
+        };
+        match reserved {
+            Ok(()) => {
+                return Ok(ExportOutput {
+                    path,
+                    created: true,
+                })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    unreachable!()
 }
 
 #[tauri::command]
@@ -595,7 +1189,7 @@ pub fn reveal_in_folder(path: String) -> Result<(), String> {
 pub struct HistoryItem {
     pub path: String,
     pub filename: String,
-    pub file_type: String, // "screenshot" or "gif"
+    pub file_type: String, // "screenshot", "gif" (any animated image) or "video"
     pub modified: u64,     // unix timestamp
     pub size: u64,         // file size in bytes
     pub thumbnail: String, // base64 data URL
@@ -613,11 +1207,26 @@ pub struct StatsResponse {
     pub total_count: usize,
     pub screenshot_count: usize,
     pub gif_count: usize,
+    pub video_count: usize,
     pub total_size: u64,
     pub today_count: usize,
     pub week_count: usize,
 }
 
+/// Classify a file in the output directory as "screenshot", "gif" (animated
+/// GIF/APNG/WebP) or "video"; None for files that aren't captures
+fn classify_capture(path: &Path) -> Option<&'static str> {
+    let ext = path.extension().and_then(|e| e.to_str())?.to_lowercase();
+    match ext.as_str() {
+        "png" if animation::is_animated_png(path) => Some("gif"),
+        "png" | "jpg" | "jpeg" => Some("screenshot"),
+        "gif" => Some("gif"),
+        "webp" if animation::is_animated_webp(path) => Some("gif"),
+        "webm" => Some("video"),
+        _ => None,
+    }
+}
+
 struct FileInfo {
     path: PathBuf,
     filename: String,
@@ -656,17 +1265,14 @@ pub async fn get_history(
                 continue;
             }
 
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
             let filename = path
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("")
                 .to_string();
 
-            let file_type = match ext.to_lowercase().as_str() {
-                "png" | "jpg" | "jpeg" => "screenshot",
-                "gif" => "gif",
-                _ => continue,
+            let Some(file_type) = classify_capture(&path) else {
+                continue;
             };
 
             let metadata = entry.metadata().ok();
@@ -748,6 +1354,7 @@ pub async fn get_stats() -> Result<StatsResponse, String> {
                 total_count: 0,
                 screenshot_count: 0,
                 gif_count: 0,
+                video_count: 0,
                 total_size: 0,
                 today_count: 0,
                 week_count: 0,
@@ -765,6 +1372,7 @@ pub async fn get_stats() -> Result<StatsResponse, String> {
 
         let mut screenshot_count = 0usize;
         let mut gif_count = 0usize;
+        let mut video_count = 0usize;
         let mut total_size = 0u64;
         let mut today_count = 0usize;
         let mut week_count = 0usize;
@@ -775,18 +1383,11 @@ pub async fn get_stats() -> Result<StatsResponse, String> {
                 continue;
             }
 
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            let is_screenshot = matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg");
-            let is_gif = ext.to_lowercase() == "gif";
-
-            if !is_screenshot && !is_gif {
-                continue;
-            }
-
-            if is_screenshot {
-                screenshot_count += 1;
-            } else {
-                gif_count += 1;
+            match classify_capture(&path) {
+                Some("screenshot") => screenshot_count += 1,
+                Some("gif") => gif_count += 1,
+                Some("video") => video_count += 1,
+                _ => continue,
             }
 
             if let Ok(meta) = entry.metadata() {
@@ -807,9 +1408,10 @@ pub async fn get_stats() -> Result<StatsResponse, String> {
         }
 
         Ok(StatsResponse {
-            total_count: screenshot_count + gif_count,
+            total_count: screenshot_count + gif_count + video_count,
             screenshot_count,
             gif_count,
+            video_count,
             total_size,
             today_count,
             week_count,
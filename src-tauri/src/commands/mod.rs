@@ -1,17 +1,21 @@
 mod config;
+mod edits;
 mod export;
 mod mouse;
 mod permission;
 mod recording;
+mod recovery;
 mod screen;
 mod scroll;
 mod selector;
 
 pub use config::*;
+pub use edits::*;
 pub use export::*;
 pub use mouse::*;
 pub use permission::*;
 pub use recording::*;
+pub use recovery::*;
 pub use screen::*;
 pub use scroll::*;
 pub use selector::*;
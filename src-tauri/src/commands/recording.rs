@@ -2,17 +2,34 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::capture::Screen;
+use crate::config;
+use crate::cursor;
+use crate::frame_store::{FrameStore, SessionManifest};
+use crate::keystrokes::KeyLogger;
 use image::RgbaImage;
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::shortcuts::{register_stop_shortcuts, unregister_stop_shortcuts};
-use crate::state::SharedState;
+use crate::shortcuts::{register_recording_shortcuts, unregister_recording_shortcuts};
+use crate::state::{AppState, RecordingSession, SharedState};
 use crate::tray::{create_recording_overlay, update_tray_icon};
-use crate::types::{RecordingInfo, RecordingState};
+use crate::types::{
+    CaptureMode, KeyEvent, RecordingInfo, RecordingState, StopReason, TimedAnnotation,
+    TimedRedaction, TimelapseOptions,
+};
 use crate::windows::{open_editor_window, set_activation_policy};
 
+/// Keyboard poll rate while logging keystrokes between frames
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How quickly a long timelapse wait or a pause notices stop/pause/resume
+const STATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 #[tauri::command]
-pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Result<(), String> {
+pub fn start_recording(
+    app: AppHandle,
+    state: tauri::State<SharedState>,
+    mode: Option<CaptureMode>,
+    timelapse: Option<TimelapseOptions>,
+) -> Result<(), String> {
     println!("[DEBUG][start_recording] ====== 被调用 ======");
     let mut s = state.lock().unwrap();
     if s.recording {
@@ -25,17 +42,53 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
         "[DEBUG][start_recording] region: x={}, y={}, w={}, h={}",
         region.x, region.y, region.width, region.height
     );
-    s.recording = true;
-    s.frames.clear();
+    let recording_mode = match mode {
+        Some(CaptureMode::Video) => CaptureMode::Video,
+        _ => CaptureMode::Gif,
+    };
+    let timelapse = timelapse.map(|t| TimelapseOptions {
+        interval_ms: t.interval_ms.clamp(
+            TimelapseOptions::MIN_INTERVAL_MS,
+            TimelapseOptions::MAX_INTERVAL_MS,
+        ),
+        ..t
+    });
+    let recording_config = config::load_config().recording.sanitized();
+    let recording_fps = recording_config.fps;
 
-    let recording_fps = s.recording_fps;
+    // Frames are written to a session directory as they arrive, so the recording
+    // can be recovered if the app dies before it's exported. Other editors keep
+    // their own sessions.
+    let mut frames = FrameStore::new();
+    frames.start_session(SessionManifest::new(
+        recording_mode,
+        region.clone(),
+        recording_fps,
+        timelapse,
+    ))?;
+    let session_id = frames.session_id().ok_or("Recording session not started")?;
+    println!("[DEBUG][start_recording] session: {}", session_id);
+    s.sessions.insert(
+        session_id.clone(),
+        RecordingSession::new(frames, recording_mode, recording_fps, timelapse),
+    );
+    s.recording_session = Some(session_id.clone());
+    s.recording = true;
+    s.recording_paused = false;
     drop(s);
 
-    // Register stop shortcuts (ESC, etc.) now that we're recording
-    register_stop_shortcuts(&app);
+    // 0 means unlimited. Timelapses are meant to run long and store few frames,
+    // so only the disk budget applies to them.
+    let max_duration_ms = Some(recording_config.max_duration_secs as u64 * 1000)
+        .filter(|&ms| ms > 0 && timelapse.is_none());
+    let max_bytes = Some(recording_config.max_disk_mb as u64 * 1024 * 1024).filter(|&b| b > 0);
+    let mut key_logger = recording_config.log_keystrokes.then(KeyLogger::new);
+
+    // Register stop/pause shortcuts (ESC, etc.) now that we're recording
+    register_recording_shortcuts(&app);
 
-    update_tray_icon(&app, true);
-    create_recording_overlay(&app, &region, false);
+    update_tray_icon(&app, true, false);
+    create_recording_overlay(&app, &region, false, None);
 
     let state_clone = state.inner().clone();
     let app_clone = app.clone();
@@ -55,66 +108,179 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
             screen.display_info.scale_factor,
             recording_fps
         );
-        let frame_duration = Duration::from_millis(1000 / recording_fps as u64);
+        let frame_duration = match timelapse {
+            Some(t) => {
+                println!(
+                    "[DEBUG][recording_thread] 延时摄影: 每 {}ms 一帧",
+                    t.interval_ms
+                );
+                Duration::from_millis(t.interval_ms)
+            }
+            None => Duration::from_millis(1000 / recording_fps as u64),
+        };
+        let skip_identical = timelapse.is_some_and(|t| t.skip_identical);
+        let mut last_capture: Option<RgbaImage> = None;
+        let mut skipped = 0u32;
 
+        // Frames are timestamped when their capture starts, so slow captures
+        // show up as longer gaps instead of speeding up playback. Paused time is
+        // left out, so the timeline continues where it stopped.
+        let recording_start = Instant::now();
+        let mut paused_total = Duration::ZERO;
+        let mut paused_since: Option<Instant> = None;
         let mut frame_idx = 0u32;
         loop {
             let start = Instant::now();
 
-            {
+            let (mut recording, paused, stored_bytes) = {
                 let s = state_clone.lock().unwrap();
-                if !s.recording {
-                    let frame_count = s.frames.len();
-                    println!(
-                        "[DEBUG][recording_thread] 录制停止，共捕获 {} 帧",
-                        frame_count
-                    );
-                    drop(s);
+                let stored_bytes = s
+                    .session(&session_id)
+                    .map_or(0, |session| session.frames.stored_bytes());
+                (
+                    is_recording_into(&s, &session_id),
+                    s.recording_paused,
+                    stored_bytes,
+                )
+            };
+            match (paused, paused_since) {
+                (true, None) => paused_since = Some(start),
+                (false, Some(since)) => {
+                    paused_total += start.duration_since(since);
+                    paused_since = None;
+                }
+                _ => {}
+            }
+            let paused_now = paused_since.map_or(Duration::ZERO, |since| start - since);
+            let timestamp_ms = (start.duration_since(recording_start) - paused_total - paused_now)
+                .as_millis() as u64;
 
-                    update_tray_icon(&app_clone, false);
+            let limit_hit = if !recording || paused {
+                None
+            } else if max_duration_ms.is_some_and(|max| timestamp_ms >= max) {
+                Some(StopReason::MaxDuration)
+            } else if max_bytes.is_some_and(|max| stored_bytes >= max) {
+                Some(StopReason::MaxDisk)
+            } else {
+                None
+            };
+            if let Some(reason) = limit_hit {
+                println!(
+                    "[DEBUG][recording_thread] 达到录制上限 {:?}，自动停止",
+                    reason
+                );
+                if let Ok(session) = state_clone.lock().unwrap().session_mut(&session_id) {
+                    session.stop_reason = reason;
+                }
+                stop_recording_internal(&app_clone, &state_clone);
+                recording = false;
+            }
 
-                    if let Some(overlay) = app_clone.get_webview_window("recording-overlay") {
-                        let _ = overlay.close();
+            if !recording {
+                let (frame_count, reason) = {
+                    let mut s = state_clone.lock().unwrap();
+                    // A new recording may have started already; leave its state alone
+                    if s.recording_session.as_deref() == Some(session_id.as_str()) {
+                        s.recording_session = None;
+                        s.recording_paused = false;
                     }
+                    s.session_mut(&session_id)
+                        .map(|session| {
+                            session.frames.finish(timestamp_ms);
+                            (session.frames.len(), session.stop_reason)
+                        })
+                        .unwrap_or((0, StopReason::User))
+                };
+                println!(
+                    "[DEBUG][recording_thread] 录制停止，共捕获 {} 帧，跳过相同帧 {}",
+                    frame_count, skipped
+                );
 
-                    // Switch to Regular activation policy so window stays visible after cmd+tab
-                    set_activation_policy(0);
+                update_tray_icon(&app_clone, false, false);
 
-                    // Open a new editor window
-                    if let Err(e) = open_editor_window(&app_clone) {
-                        println!("[DEBUG][recording_thread] 打开编辑器窗口失败: {}", e);
-                    }
+                if let Some(overlay) = app_clone.get_webview_window("recording-overlay") {
+                    let _ = overlay.close();
+                }
 
-                    let _ = app_clone.emit(
-                        "recording-stopped",
-                        serde_json::json!({
-                            "frame_count": frame_count
-                        }),
-                    );
-                    break;
+                // Switch to Regular activation policy so window stays visible after cmd+tab
+                set_activation_policy(0);
+
+                // Open a new editor window for this session
+                if let Err(e) = open_editor_window(&app_clone, recording_mode, &session_id) {
+                    println!("[DEBUG][recording_thread] 打开编辑器窗口失败: {}", e);
                 }
+
+                let _ = app_clone.emit(
+                    "recording-stopped",
+                    serde_json::json!({
+                        "session_id": session_id,
+                        "frame_count": frame_count,
+                        "reason": reason,
+                    }),
+                );
+                break;
+            }
+
+            if paused {
+                thread::sleep(frame_duration.min(STATE_POLL_INTERVAL));
+                continue;
             }
 
+            // Sampled next to the capture so the pointer lines up with the frame
+            let cursor = cursor::sample(&region, screen.display_info.scale_factor);
             match screen.capture_area(region.x, region.y, region.width, region.height) {
                 Ok(img) => {
                     let rgba =
                         RgbaImage::from_raw(img.width(), img.height(), img.into_raw()).unwrap();
+                    // Nothing changed: the previous frame just stays on screen
+                    // longer, since durations come from timestamps
+                    if skip_identical && last_capture.as_ref() == Some(&rgba) {
+                        skipped += 1;
+                        wait_for_next_tick(
+                            key_logger.as_mut(),
+                            &state_clone,
+                            &session_id,
+                            start,
+                            timestamp_ms,
+                            start + frame_duration,
+                        );
+                        continue;
+                    }
+                    if skip_identical {
+                        last_capture = Some(rgba.clone());
+                    }
+                    // Compress before taking the lock so UI commands aren't blocked
+                    let compressed = FrameStore::compress(&rgba);
 
                     let mut s = state_clone.lock().unwrap();
-                    s.frames.push(rgba);
-                    frame_idx += 1;
+                    let pushed = s.session_mut(&session_id).and_then(|session| {
+                        session
+                            .frames
+                            .push_compressed(rgba, &compressed, timestamp_ms, cursor)?;
+                        Ok(session.frames.len())
+                    });
+                    drop(s);
+                    match pushed {
+                        Ok(frame_count) => {
+                            frame_idx += 1;
 
-                    if frame_idx <= 3 || frame_idx % 10 == 0 {
-                        println!("[DEBUG][recording_thread] 捕获帧 #{}", frame_idx);
-                    }
+                            if frame_idx <= 3 || frame_idx % 10 == 0 {
+                                println!("[DEBUG][recording_thread] 捕获帧 #{}", frame_idx);
+                            }
 
-                    let _ = app_clone.emit(
-                        "recording-state",
-                        RecordingState {
-                            is_recording: true,
-                            frame_count: s.frames.len() as u32,
-                        },
-                    );
+                            let _ = app_clone.emit(
+                                "recording-state",
+                                RecordingState {
+                                    is_recording: true,
+                                    is_paused: false,
+                                    frame_count: frame_count as u32,
+                                },
+                            );
+                        }
+                        Err(e) => {
+                            println!("[DEBUG][recording_thread] 写入帧失败: {}", e);
+                        }
+                    }
                 }
                 Err(e) => {
                     if frame_idx == 0 {
@@ -127,10 +293,14 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
                 }
             }
 
-            let elapsed = start.elapsed();
-            if elapsed < frame_duration {
-                thread::sleep(frame_duration - elapsed);
-            }
+            wait_for_next_tick(
+                key_logger.as_mut(),
+                &state_clone,
+                &session_id,
+                start,
+                timestamp_ms,
+                start + frame_duration,
+            );
         }
         println!("[DEBUG][recording_thread] 线程退出");
     });
@@ -138,47 +308,226 @@ pub fn start_recording(app: AppHandle, state: tauri::State<SharedState>) -> Resu
     Ok(())
 }
 
+/// Sleep until `deadline`, polling the keyboard (if logging) so presses between
+/// frames are caught, and returning early once the recording is stopped or
+/// paused so long timelapse intervals don't delay either
+fn wait_for_next_tick(
+    mut key_logger: Option<&mut KeyLogger>,
+    state: &SharedState,
+    session_id: &str,
+    tick_start: Instant,
+    tick_timestamp_ms: u64,
+    deadline: Instant,
+) {
+    let poll_interval = match key_logger {
+        Some(_) => KEY_POLL_INTERVAL,
+        None => STATE_POLL_INTERVAL,
+    };
+    loop {
+        let now = Instant::now();
+        if let Some(logger) = key_logger.as_deref_mut() {
+            let timestamp_ms =
+                tick_timestamp_ms + now.duration_since(tick_start).as_millis() as u64;
+            let events = logger.poll(timestamp_ms);
+            if !events.is_empty() {
+                if let Ok(session) = state.lock().unwrap().session_mut(session_id) {
+                    session.frames.push_keystrokes(events);
+                }
+            }
+        }
+        if now >= deadline {
+            break;
+        }
+        {
+            let s = state.lock().unwrap();
+            if !is_recording_into(&s, session_id) || s.recording_paused {
+                break;
+            }
+        }
+        thread::sleep((deadline - now).min(poll_interval));
+    }
+}
+
+/// The recording thread for `session_id` keeps going only while this holds
+fn is_recording_into(s: &AppState, session_id: &str) -> bool {
+    s.recording && s.recording_session.as_deref() == Some(session_id)
+}
+
 #[tauri::command]
 pub fn stop_recording(app: AppHandle, state: tauri::State<SharedState>) {
     println!("[DEBUG][stop_recording] ====== 被调用 ======");
+    stop_recording_internal(&app, state.inner());
+}
+
+/// Stop recording; the recording thread notices on its next tick and opens the editor
+pub fn stop_recording_internal(app: &AppHandle, state: &SharedState) {
     let mut s = state.lock().unwrap();
     s.recording = false;
     println!("[DEBUG][stop_recording] 录制标志已设置为 false");
     drop(s);
 
-    // Unregister stop shortcuts since recording ended
-    unregister_stop_shortcuts(&app);
+    // Unregister stop/pause shortcuts since recording ended
+    unregister_recording_shortcuts(app);
+}
+
+#[tauri::command]
+pub fn pause_recording(app: AppHandle, state: tauri::State<SharedState>) -> Result<(), String> {
+    set_recording_paused_internal(&app, state.inner(), true)
 }
 
 #[tauri::command]
-pub fn get_recording_info(state: tauri::State<SharedState>) -> RecordingInfo {
+pub fn resume_recording(app: AppHandle, state: tauri::State<SharedState>) -> Result<(), String> {
+    set_recording_paused_internal(&app, state.inner(), false)
+}
+
+/// Flip between paused and recording (shortcut / tray menu)
+pub fn toggle_recording_pause_internal(app: &AppHandle, state: &SharedState) -> Result<(), String> {
+    let paused = state.lock().unwrap().recording_paused;
+    set_recording_paused_internal(app, state, !paused)
+}
+
+/// Pause or resume the current recording and update tray + overlay.
+/// Emits "recording-paused" with the new state.
+pub fn set_recording_paused_internal(
+    app: &AppHandle,
+    state: &SharedState,
+    paused: bool,
+) -> Result<(), String> {
+    let mut s = state.lock().unwrap();
+    if !s.recording {
+        return Err("Not recording".to_string());
+    }
+    if s.recording_paused == paused {
+        return Ok(());
+    }
+    s.recording_paused = paused;
+    let frame_count = s
+        .recording_session
+        .as_deref()
+        .and_then(|id| s.sessions.get(id))
+        .map_or(0, |session| session.frames.len() as u32);
+    drop(s);
+
+    println!(
+        "[DEBUG][recording] {}",
+        if paused {
+            "暂停录制"
+        } else {
+            "继续录制"
+        }
+    );
+    update_tray_icon(app, true, paused);
+    let _ = app.emit(
+        "recording-state",
+        RecordingState {
+            is_recording: true,
+            is_paused: paused,
+            frame_count,
+        },
+    );
+    let _ = app.emit("recording-paused", serde_json::json!({ "paused": paused }));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recording_info(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<RecordingInfo, String> {
     let s = state.lock().unwrap();
-    let (width, height) = if let Some(frame) = s.frames.first() {
-        frame.dimensions()
-    } else {
-        (0, 0)
-    };
-    let duration_ms = if s.recording_fps > 0 {
-        (s.frames.len() as u64 * 1000) / s.recording_fps as u64
-    } else {
-        0
-    };
+    let session = s.session(&session_id)?;
+    let (width, height) = session.frames.dimensions().unwrap_or((0, 0));
 
-    RecordingInfo {
-        frame_count: s.frames.len(),
+    Ok(RecordingInfo {
+        session_id,
+        frame_count: session.frames.len(),
         width,
         height,
-        fps: s.recording_fps,
-        duration_ms,
-        has_frames: !s.frames.is_empty(),
-    }
+        fps: session.fps,
+        duration_ms: session.frames.duration_ms(),
+        has_frames: !session.frames.is_empty(),
+        mode: session.mode,
+        stop_reason: session.stop_reason,
+        timelapse_interval_ms: session.timelapse.map(|t| t.interval_ms),
+    })
+}
+
+/// Key presses logged during a recording
+#[tauri::command]
+pub fn get_keystrokes(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<Vec<KeyEvent>, String> {
+    let s = state.lock().unwrap();
+    Ok(s.session(&session_id)?.frames.keystrokes().to_vec())
+}
+
+/// Replace the key log, e.g. after editing labels or removing private input
+#[tauri::command]
+pub fn set_keystrokes(
+    state: tauri::State<SharedState>,
+    session_id: String,
+    events: Vec<KeyEvent>,
+) -> Result<(), String> {
+    let mut s = state.lock().unwrap();
+    s.session_mut(&session_id)?.frames.set_keystrokes(events);
+    Ok(())
 }
 
+/// Captions and shapes drawn on export
 #[tauri::command]
-pub fn discard_recording(app: AppHandle, state: tauri::State<SharedState>) {
-    println!("[DEBUG][discard_recording] 丢弃录制数据");
+pub fn get_annotations(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<Vec<TimedAnnotation>, String> {
+    let s = state.lock().unwrap();
+    Ok(s.session(&session_id)?.frames.annotations().to_vec())
+}
+
+/// Replace the annotation list; it's saved with the session for recovery
+#[tauri::command]
+pub fn set_annotations(
+    state: tauri::State<SharedState>,
+    session_id: String,
+    annotations: Vec<TimedAnnotation>,
+) -> Result<(), String> {
+    let mut s = state.lock().unwrap();
+    s.session_mut(&session_id)?
+        .frames
+        .set_annotations(annotations);
+    Ok(())
+}
+
+/// Areas to scrub that aren't burned into the frames yet
+#[tauri::command]
+pub fn get_redactions(
+    state: tauri::State<SharedState>,
+    session_id: String,
+) -> Result<Vec<TimedRedaction>, String> {
+    let s = state.lock().unwrap();
+    Ok(s.session(&session_id)?.frames.redactions())
+}
+
+#[tauri::command]
+pub fn set_redactions(
+    state: tauri::State<SharedState>,
+    session_id: String,
+    redactions: Vec<TimedRedaction>,
+) -> Result<(), String> {
+    let mut s = state.lock().unwrap();
+    s.session_mut(&session_id)?
+        .frames
+        .set_redactions(redactions);
+    Ok(())
+}
+
+/// Delete a recording; other editors' sessions are untouched
+#[tauri::command]
+pub fn discard_recording(app: AppHandle, state: tauri::State<SharedState>, session_id: String) {
+    println!("[DEBUG][discard_recording] 丢弃录制数据: {}", session_id);
     let mut s = state.lock().unwrap();
-    s.frames.clear();
+    // Dropping the session deletes its directory
+    s.sessions.remove(&session_id);
     drop(s);
 
     // Hide main window and switch back to Accessory policy
@@ -187,3 +536,16 @@ pub fn discard_recording(app: AppHandle, state: tauri::State<SharedState>) {
         set_activation_policy(1);
     }
 }
+
+/// Unload a session whose editor window closed. An unexported recording stays on
+/// disk and can be recovered later.
+pub fn release_session(state: &SharedState, session_id: &str) {
+    let mut s = state.lock().unwrap();
+    if let Some(session) = s.sessions.remove(session_id) {
+        println!(
+            "[DEBUG][release_session] 编辑器已关闭，释放会话: {}",
+            session_id
+        );
+        session.frames.release();
+    }
+}
@@ -0,0 +1,100 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::frame_store::{self, FrameStore};
+use crate::state::{RecordingSession, SharedState};
+use crate::types::RecoverableSession;
+use crate::windows::{open_editor_window, set_activation_policy};
+
+/// Unexported recordings not open in an editor (e.g. left by a crash), newest first
+#[tauri::command]
+pub fn list_recoverable_sessions() -> Vec<RecoverableSession> {
+    frame_store::recoverable_sessions()
+}
+
+/// Load a session from a previous run and open it in the editor
+#[tauri::command]
+pub fn recover_session(
+    app: AppHandle,
+    state: tauri::State<SharedState>,
+    id: String,
+) -> Result<(), String> {
+    recover_session_internal(&app, &state, &id)
+}
+
+/// Delete a session from a previous run without opening it
+#[tauri::command]
+pub fn discard_session(id: String) -> Result<(), String> {
+    println!("[DEBUG][discard_session] 删除录制会话: {}", id);
+    frame_store::remove_session(&id)
+}
+
+pub fn recover_session_internal(
+    app: &AppHandle,
+    state: &SharedState,
+    id: &str,
+) -> Result<(), String> {
+    println!("[DEBUG][recover_session] 恢复录制会话: {}", id);
+    let (frames, manifest) = FrameStore::open_session(id)?;
+    // Recovered sessions keep their ID, next to any other open recordings
+    let session_id = frames.session_id().ok_or("Recording session not found")?;
+    let saved_edits = frames.saved_edits();
+    let mut session =
+        RecordingSession::new(frames, manifest.mode, manifest.fps, manifest.timelapse);
+    if let Some(edits) = saved_edits {
+        session.edits = edits;
+    }
+    state
+        .lock()
+        .unwrap()
+        .sessions
+        .insert(session_id.clone(), session);
+
+    set_activation_policy(0);
+    open_editor_window(app, manifest.mode, &session_id)
+}
+
+/// Ask whether to reopen the most recent unexported recording (called at launch).
+/// "Later" keeps it on disk until it's recovered or expires.
+pub fn offer_session_recovery(app: &AppHandle) {
+    let Some(session) = frame_store::recoverable_sessions().into_iter().next() else {
+        return;
+    };
+    println!(
+        "[DEBUG][offer_session_recovery] 发现未导出的录制: {} ({} 帧)",
+        session.id, session.frame_count
+    );
+
+    let started = chrono::DateTime::from_timestamp_millis(session.created_ms as i64)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let message = format!(
+        "A recording from {} ({:.1}s, {} frames) was not exported before Lovshot quit. Open it in the editor?",
+        started,
+        session.duration_ms as f64 / 1000.0,
+        session.frame_count
+    );
+
+    let app_handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Recover Recording")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Open".to_string(),
+            "Later".to_string(),
+        ))
+        .show(move |open| {
+            if !open {
+                return;
+            }
+            let state = app_handle.state::<SharedState>();
+            if let Err(e) = recover_session_internal(&app_handle, &state, &session.id) {
+                println!("[DEBUG][offer_session_recovery] 恢复失败: {}", e);
+            }
+        });
+}
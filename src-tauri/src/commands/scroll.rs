@@ -9,9 +9,10 @@ use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, Webv
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
 use crate::fft_match::detect_scroll_delta_fft;
+use crate::redact;
 use crate::state::SharedState;
 use crate::tray::create_recording_overlay;
-use crate::types::{CropEdges, Region, ScrollCaptureProgress};
+use crate::types::{CropEdges, Redaction, Region, ScrollCaptureProgress};
 
 /// Start scroll capture mode - captures the initial frame
 #[tauri::command]
@@ -68,7 +69,7 @@ pub fn start_scroll_capture(
     let frame = RgbaImage::from_raw(captured.width(), captured.height(), captured.into_raw())
         .ok_or("Failed to convert image")?;
 
-    let (_width, height) = frame.dimensions();
+    let (width, height) = frame.dimensions();
 
     // Store initial frame
     let mut s = state.lock().unwrap();
@@ -86,6 +87,7 @@ pub fn start_scroll_capture(
 
     Ok(ScrollCaptureProgress {
         frame_count: 1,
+        total_width: width,
         total_height: height,
         preview_base64: preview,
     })
@@ -155,6 +157,7 @@ pub fn capture_scroll_frame_auto(
 
     Ok(Some(ScrollCaptureProgress {
         frame_count,
+        total_width: stitched.width(),
         total_height,
         preview_base64: preview,
     }))
@@ -171,6 +174,7 @@ pub fn get_scroll_preview(
         let preview = generate_preview_base64(stitched, 600)?;
         Ok(ScrollCaptureProgress {
             frame_count: s.scroll_frames.len(),
+            total_width: stitched.width(),
             total_height: stitched.height(),
             preview_base64: preview,
         })
@@ -185,11 +189,13 @@ pub fn copy_scroll_to_clipboard(
     app: AppHandle,
     state: tauri::State<SharedState>,
     crop: Option<CropEdges>,
+    redactions: Option<Vec<Redaction>>,
 ) -> Result<(), String> {
     let s = state.lock().unwrap();
     let stitched = s.scroll_stitched.as_ref().ok_or("No stitched image")?;
+    let scale = pixel_scale(stitched, s.region.as_ref());
 
-    let final_img = apply_crop(stitched, crop)?;
+    let final_img = render_final(stitched, redactions, scale, crop)?;
 
     let tauri_image = tauri::image::Image::new_owned(
         final_img.as_raw().to_vec(),
@@ -210,9 +216,11 @@ pub fn finish_scroll_capture(
     state: tauri::State<SharedState>,
     path: String,
     crop: Option<CropEdges>,
+    redactions: Option<Vec<Redaction>>,
 ) -> Result<String, String> {
     let mut s = state.lock().unwrap();
     let stitched = s.scroll_stitched.take().ok_or("No stitched image")?;
+    let scale = pixel_scale(&stitched, s.region.as_ref());
 
     // Clear scroll state
     s.scroll_capturing = false;
@@ -221,8 +229,8 @@ pub fn finish_scroll_capture(
 
     drop(s);
 
-    // Apply crop and save
-    let final_img = apply_crop(&stitched, crop)?;
+    // Apply redactions and crop, then save
+    let final_img = render_final(&stitched, redactions, scale, crop)?;
     final_img.save(&path).map_err(|e| e.to_string())?;
 
     // Close region overlay after finishing
@@ -350,6 +358,29 @@ fn stitch_scroll_image(
     }
 }
 
+/// Image pixels per logical pixel of the capture region
+fn pixel_scale(img: &RgbaImage, region: Option<&Region>) -> f32 {
+    region.map_or(1.0, |r| img.width() as f32 / r.width.max(1) as f32)
+}
+
+/// Redactions are in stitched-image pixels, so they're applied before cropping
+fn render_final(
+    img: &RgbaImage,
+    redactions: Option<Vec<Redaction>>,
+    scale: f32,
+    crop: Option<CropEdges>,
+) -> Result<RgbaImage, String> {
+    match redactions.filter(|r| !r.is_empty()) {
+        Some(redactions) => {
+            println!("[DEBUG][scroll] 打码 {} 个区域", redactions.len());
+            let mut redacted = img.clone();
+            redact::apply_all(&mut redacted, &redactions, scale);
+            apply_crop(&redacted, crop)
+        }
+        None => apply_crop(img, crop),
+    }
+}
+
 /// Apply percentage-based edge crop to an image
 fn apply_crop(img: &RgbaImage, crop: Option<CropEdges>) -> Result<RgbaImage, String> {
     let crop = match crop {
@@ -462,7 +493,7 @@ pub fn open_scroll_overlay(
     }
 
     // Show region indicator overlay (reuse recording overlay window in static mode)
-    create_recording_overlay(&app, &region, true);
+    create_recording_overlay(&app, &region, true, None);
 
     // Build window WITHOUT focus - critical for scroll events to pass through
     let win = WebviewWindowBuilder::new(
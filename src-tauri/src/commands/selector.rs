@@ -36,7 +36,7 @@ pub fn open_selector(app: AppHandle, state: tauri::State<SharedState>) -> Result
     // Only hide main window if we're starting a GIF/Video recording (not for screenshots)
     // Screenshots should not disrupt the dashboard view
     let s = state.lock().unwrap();
-    let has_frames = !s.frames.is_empty();
+    let has_frames = s.has_frames();
     let pending_mode = s.pending_mode;
     drop(s);
 
@@ -137,6 +137,13 @@ pub fn get_pending_mode(state: tauri::State<SharedState>) -> Option<CaptureMode>
     mode
 }
 
+/// Screenshot delay to preselect, when the selector was opened by the timed
+/// screenshot shortcut
+#[tauri::command]
+pub fn get_pending_timer(state: tauri::State<SharedState>) -> Option<u32> {
+    state.lock().unwrap().pending_timer_secs
+}
+
 #[tauri::command]
 pub fn get_screen_snapshot(state: tauri::State<SharedState>) -> Option<String> {
     state.lock().unwrap().screen_snapshot.clone()
@@ -177,7 +184,9 @@ pub fn get_window_info_at_cursor() -> Option<WindowInfo> {
 
 #[tauri::command]
 pub fn clear_pending_mode(state: tauri::State<SharedState>) {
-    state.lock().unwrap().pending_mode = None;
+    let mut s = state.lock().unwrap();
+    s.pending_mode = None;
+    s.pending_timer_secs = None;
 }
 
 /// Activate the window under cursor so it can receive scroll events
@@ -216,7 +225,7 @@ pub fn open_selector_internal(app: AppHandle) -> Result<(), String> {
     // Only hide main window if we're starting a GIF/Video recording (not for screenshots)
     let state = app.state::<SharedState>();
     let s = state.lock().unwrap();
-    let has_frames = !s.frames.is_empty();
+    let has_frames = s.has_frames();
     let pending_mode = s.pending_mode;
     drop(s);
 
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::types::RedactionMode;
+
 /// Shortcut configuration for a single shortcut binding
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ShortcutConfig {
@@ -50,6 +52,80 @@ pub struct AppConfig {
     pub developer_mode: bool,
     #[serde(default = "default_autostart")]
     pub autostart_enabled: bool,
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub exclusions: ExclusionConfig,
+}
+
+/// Capture rate and limits for GIF/video recordings
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RecordingConfig {
+    pub fps: u32,
+    pub max_duration_secs: u32, // 0 = unlimited, paused time doesn't count
+    #[serde(alias = "max_memory_mb")]
+    pub max_disk_mb: u32,       // cap on compressed frames spooled to disk, 0 = unlimited
+    pub log_keystrokes: bool,   // for the keystroke badge; off by default for privacy
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            fps: 30,
+            max_duration_secs: 0,
+            max_disk_mb: 2048,
+            log_keystrokes: false,
+        }
+    }
+}
+
+impl RecordingConfig {
+    pub const MIN_FPS: u32 = 1;
+    pub const MAX_FPS: u32 = 60;
+
+    /// Clamp values loaded from disk or sent by the frontend into the supported range
+    pub fn sanitized(mut self) -> Self {
+        self.fps = self.fps.clamp(Self::MIN_FPS, Self::MAX_FPS);
+        self
+    }
+}
+
+/// Apps whose windows are masked out of every capture (screenshots, recordings
+/// and scroll captures), e.g. notifications or a password manager
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExclusionConfig {
+    pub apps: Vec<String>, // app names or bundle IDs, case-insensitive
+    pub mode: RedactionMode,
+}
+
+impl Default for ExclusionConfig {
+    fn default() -> Self {
+        Self {
+            apps: Vec::new(),
+            mode: RedactionMode::Solid,
+        }
+    }
+}
+
+impl ExclusionConfig {
+    /// Trim entries sent by the frontend and drop empty or duplicate ones
+    pub fn sanitized(mut self) -> Self {
+        let mut seen = Vec::new();
+        self.apps = self
+            .apps
+            .into_iter()
+            .map(|app| app.trim().to_string())
+            .filter(|app| {
+                let key = app.to_lowercase();
+                let keep = !app.is_empty() && !seen.contains(&key);
+                seen.push(key);
+                keep
+            })
+            .collect();
+        self
+    }
 }
 
 /// Old config format for migration
@@ -75,6 +151,8 @@ impl From<OldAppConfig> for AppConfig {
             shortcuts,
             developer_mode: old.developer_mode,
             autostart_enabled: old.autostart_enabled,
+            recording: RecordingConfig::default(),
+            exclusions: ExclusionConfig::default(),
         }
     }
 }
@@ -114,6 +192,16 @@ impl Default for AppConfig {
             }],
         );
 
+        // Opens the selector with the screenshot timer on
+        shortcuts.insert(
+            "timed_screenshot".to_string(),
+            vec![ShortcutConfig {
+                modifiers: vec!["Alt".to_string()],
+                key: "T".to_string(),
+                enabled: true,
+            }],
+        );
+
         shortcuts.insert(
             "scroll".to_string(),
             vec![ShortcutConfig {
@@ -134,6 +222,16 @@ impl Default for AppConfig {
             }],
         );
 
+        // Pauses/resumes a GIF recording; only active while recording
+        shortcuts.insert(
+            "pause_recording".to_string(),
+            vec![ShortcutConfig {
+                modifiers: vec!["Alt".to_string()],
+                key: "P".to_string(),
+                enabled: true,
+            }],
+        );
+
         shortcuts.insert(
             "show_main".to_string(),
             vec![ShortcutConfig {
@@ -148,6 +246,8 @@ impl Default for AppConfig {
             shortcuts,
             developer_mode: false,
             autostart_enabled: true,
+            recording: RecordingConfig::default(),
+            exclusions: ExclusionConfig::default(),
         }
     }
 }
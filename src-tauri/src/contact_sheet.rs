@@ -0,0 +1,253 @@
+//! Contact sheet (sprite sheet) rendering
+//!
+//! Lays sampled frames out in a grid with optional labels below each cell, and
+//! writes a JSON and CSS descriptor next to the PNG so the sheet can be used as
+//! a web sprite. Labels use a small built-in bitmap font (digits and `#:.`), so
+//! no font files are needed.
+
+use std::path::Path;
+
+use image::{imageops, Rgba, RgbaImage};
+use serde::Serialize;
+
+use crate::types::{ContactSheetConfig, SheetLabel};
+
+const BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LABEL_COLOR: Rgba<u8> = Rgba([60, 60, 60, 255]);
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// 5x7 glyphs, one byte per row, bit 4 = leftmost pixel
+fn glyph(c: char) -> Option<[u8; 7]> {
+    Some(match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        _ => return None,
+    })
+}
+
+/// Sprite sheet descriptor, written as `<name>.json`
+#[derive(Serialize)]
+struct SheetDescriptor {
+    image: String,
+    width: u32,
+    height: u32,
+    columns: u32,
+    rows: u32,
+    cell_width: u32,
+    cell_height: u32,
+    frames: Vec<SpriteFrame>,
+}
+
+/// One cell; `x`/`y`/`width`/`height` cover the frame image, not its label
+#[derive(Clone, Serialize)]
+struct SpriteFrame {
+    frame: usize,
+    timestamp_ms: u64,
+    label: String,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// A sheet being filled cell by cell, so frames can be dropped once drawn
+pub struct ContactSheet {
+    canvas: RgbaImage,
+    label: SheetLabel,
+    padding: u32,
+    columns: u32,
+    rows: u32,
+    cell_width: u32,
+    cell_height: u32,
+    label_scale: u32,
+    label_height: u32,
+    frames: Vec<SpriteFrame>,
+}
+
+impl ContactSheet {
+    /// Allocate a sheet for `count` frames of the given size; cells shrink
+    /// (never grow) so the sheet stays within `max_width`
+    pub fn new(
+        frame_width: u32,
+        frame_height: u32,
+        count: usize,
+        config: &ContactSheetConfig,
+    ) -> Self {
+        let count = count.max(1) as u32;
+        let columns = config.columns.clamp(1, count);
+        let rows = count.div_ceil(columns);
+        let padding = config.padding;
+
+        let available = config
+            .max_width
+            .saturating_sub(padding * (columns + 1))
+            .max(columns);
+        let cell_width = frame_width.min(available / columns).max(1);
+        let cell_height =
+            (frame_height as u64 * cell_width as u64 / frame_width.max(1) as u64).max(1) as u32;
+
+        let label_scale = (cell_width / 80).clamp(1, 3);
+        let label_height = match config.label {
+            SheetLabel::None => 0,
+            _ => (GLYPH_HEIGHT + 4) * label_scale,
+        };
+
+        let width = padding + columns * (cell_width + padding);
+        let height = padding + rows * (cell_height + label_height + padding);
+
+        Self {
+            canvas: RgbaImage::from_pixel(width, height, BACKGROUND),
+            label: config.label,
+            padding,
+            columns,
+            rows,
+            cell_width,
+            cell_height,
+            label_scale,
+            label_height,
+            frames: Vec::with_capacity(count as usize),
+        }
+    }
+
+    /// Draw the next frame into its cell; `index` is the recording frame number
+    pub fn add(&mut self, frame: &RgbaImage, index: usize, timestamp_ms: u64) {
+        let cell = self.frames.len() as u32;
+        if cell >= self.columns * self.rows {
+            return;
+        }
+        let x = self.padding + (cell % self.columns) * (self.cell_width + self.padding);
+        let y = self.padding
+            + (cell / self.columns) * (self.cell_height + self.label_height + self.padding);
+
+        let thumbnail = if frame.dimensions() == (self.cell_width, self.cell_height) {
+            frame.clone()
+        } else {
+            imageops::resize(
+                frame,
+                self.cell_width,
+                self.cell_height,
+                imageops::FilterType::Triangle,
+            )
+        };
+        imageops::replace(&mut self.canvas, &thumbnail, x as i64, y as i64);
+
+        let label = match self.label {
+            SheetLabel::None => String::new(),
+            SheetLabel::FrameNumber => format!("#{}", index + 1),
+            SheetLabel::Timestamp => format_timestamp(timestamp_ms),
+        };
+        if !label.is_empty() {
+            let text_y = y + self.cell_height + 2 * self.label_scale;
+            self.draw_text(&label, x, text_y);
+        }
+
+        self.frames.push(SpriteFrame {
+            frame: index,
+            timestamp_ms,
+            label,
+            x,
+            y,
+            width: self.cell_width,
+            height: self.cell_height,
+        });
+    }
+
+    /// Centered in the cell; characters that don't fit are dropped
+    fn draw_text(&mut self, text: &str, cell_x: u32, y: u32) {
+        let scale = self.label_scale;
+        let advance = (GLYPH_WIDTH + 1) * scale;
+        let fits = (self.cell_width / advance) as usize;
+        let chars: Vec<char> = text.chars().take(fits).collect();
+        let text_width = (chars.len() as u32 * advance).saturating_sub(scale);
+        let mut x = cell_x + (self.cell_width - text_width.min(self.cell_width)) / 2;
+
+        for c in chars {
+            if let Some(rows) = glyph(c) {
+                for (row, bits) in rows.iter().enumerate() {
+                    for col in 0..GLYPH_WIDTH {
+                        if bits & (0x10 >> col) == 0 {
+                            continue;
+                        }
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                let px = x + col * scale + dx;
+                                let py = y + row as u32 * scale + dy;
+                                if px < self.canvas.width() && py < self.canvas.height() {
+                                    self.canvas.put_pixel(px, py, LABEL_COLOR);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            x += advance;
+        }
+    }
+
+    /// Write the PNG plus `<name>.json` and `<name>.css` next to it
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let image_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "sheet.png".to_string());
+        let (width, height) = self.canvas.dimensions();
+
+        let descriptor = SheetDescriptor {
+            image: image_name,
+            width,
+            height,
+            columns: self.columns,
+            rows: self.rows,
+            cell_width: self.cell_width,
+            cell_height: self.cell_height,
+            frames: self.frames.clone(),
+        };
+
+        self.canvas
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+
+        let json = serde_json::to_vec_pretty(&descriptor).map_err(|e| e.to_string())?;
+        std::fs::write(path.with_extension("json"), json).map_err(|e| e.to_string())?;
+        std::fs::write(path.with_extension("css"), sprite_css(&descriptor))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// `.sprite` sets the sheet and cell size; `.sprite-N` picks the N-th cell
+fn sprite_css(descriptor: &SheetDescriptor) -> String {
+    let mut css = format!(
+        ".sprite {{\n  background-image: url(\"{}\");\n  background-repeat: no-repeat;\n  display: inline-block;\n  width: {}px;\n  height: {}px;\n}}\n",
+        descriptor.image, descriptor.cell_width, descriptor.cell_height
+    );
+    for (i, f) in descriptor.frames.iter().enumerate() {
+        css.push_str(&format!(
+            "\n.sprite-{} {{\n  background-position: -{}px -{}px;\n}}\n",
+            i, f.x, f.y
+        ));
+    }
+    css
+}
+
+/// `m:ss.cc`
+fn format_timestamp(ms: u64) -> String {
+    format!(
+        "{}:{:02}.{:02}",
+        ms / 60_000,
+        ms / 1000 % 60,
+        ms % 1000 / 10
+    )
+}
@@ -0,0 +1,214 @@
+//! Mouse cursor capture and rendering for recordings
+//!
+//! xcap captures don't include the pointer, so the recorder samples its position
+//! and left-button state with every frame. The cursor sprite and click rings are
+//! drawn at export time, which keeps them optional after recording.
+
+use image::{Rgba, RgbaImage};
+use mouse_position::mouse_position::Mouse;
+use serde::{Deserialize, Serialize};
+
+use crate::draw;
+use crate::frame_store::FrameSnapshot;
+use crate::types::Region;
+
+/// How long a click ring takes to expand and fade out
+const CLICK_RING_MS: u64 = 400;
+const CLICK_RING_COLOR: Rgba<u8> = Rgba([255, 196, 0, 255]);
+
+/// Classic arrow, hotspot at the top-left. `X` = outline, `.` = fill
+const ARROW: [&str; 19] = [
+    "X",
+    "XX",
+    "X.X",
+    "X..X",
+    "X...X",
+    "X....X",
+    "X.....X",
+    "X......X",
+    "X.......X",
+    "X........X",
+    "X.........X",
+    "X......XXXXX",
+    "X...X..X",
+    "X..XX..X",
+    "X.X  X..X",
+    "XX   X..X",
+    "X     X..X",
+    "      X..X",
+    "       XX",
+];
+
+/// Pointer state at the moment a frame was captured
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CursorSample {
+    /// Position relative to the frame's top-left, in frame pixels
+    pub x: f32,
+    pub y: f32,
+    /// Frame pixels per logical pixel, so the sprite matches the display's size
+    pub scale: f32,
+    pub pressed: bool,
+}
+
+/// Read the pointer relative to `region`; `scale` converts logical to frame pixels
+pub fn sample(region: &Region, scale: f32) -> Option<CursorSample> {
+    let Mouse::Position { x, y } = Mouse::get_mouse_position() else {
+        return None;
+    };
+    Some(CursorSample {
+        x: (x - region.x) as f32 * scale,
+        y: (y - region.y) as f32 * scale,
+        scale,
+        pressed: left_button_pressed(),
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn left_button_pressed() -> bool {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceButtonState(state_id: i32, button: u32) -> bool;
+    }
+    // kCGEventSourceStateCombinedSessionState, kCGMouseButtonLeft
+    unsafe { CGEventSourceButtonState(0, 0) }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn left_button_pressed() -> bool {
+    false
+}
+
+struct Click {
+    time_ms: u64,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+/// Cursor and click rings for one export
+pub struct CursorOverlay<'a> {
+    samples: &'a [Option<CursorSample>],
+    show_cursor: bool,
+    clicks: Vec<Click>,
+}
+
+impl<'a> CursorOverlay<'a> {
+    /// Returns None when there is nothing to draw
+    pub fn new(
+        frames: &'a FrameSnapshot,
+        show_cursor: bool,
+        highlight_clicks: bool,
+    ) -> Option<Self> {
+        let samples = frames.cursor_samples();
+        if !show_cursor && !highlight_clicks || samples.iter().all(Option::is_none) {
+            return None;
+        }
+
+        // A click starts on every frame where the button went down
+        let mut clicks = Vec::new();
+        if highlight_clicks {
+            let mut was_pressed = false;
+            for (i, s) in samples.iter().enumerate() {
+                let pressed = s.is_some_and(|s| s.pressed);
+                if let Some(s) = s.filter(|_| pressed && !was_pressed) {
+                    clicks.push(Click {
+                        time_ms: frames.timestamp_ms(i),
+                        x: s.x,
+                        y: s.y,
+                        scale: s.scale,
+                    });
+                }
+                was_pressed = pressed;
+            }
+        }
+
+        Some(Self {
+            samples,
+            show_cursor,
+            clicks,
+        })
+    }
+
+    /// Whether a click ring is on screen anywhere in `from_ms..=to_ms`
+    pub fn changes_between(&self, from_ms: u64, to_ms: u64) -> bool {
+        self.clicks
+            .iter()
+            .any(|c| c.time_ms <= to_ms && ring_progress(c, from_ms.max(c.time_ms)).is_some())
+    }
+
+    /// Draw onto frame `index`, shown at `time_ms` on the recording timeline
+    pub fn apply(&self, frame: &mut RgbaImage, index: usize, time_ms: u64) {
+        for click in &self.clicks {
+            if let Some(progress) = ring_progress(click, time_ms) {
+                draw_ring(frame, click, progress);
+            }
+        }
+        if self.show_cursor {
+            if let Some(Some(s)) = self.samples.get(index) {
+                draw_arrow(frame, s);
+            }
+        }
+    }
+}
+
+/// 0.0..1.0 while the ring is visible
+fn ring_progress(click: &Click, time_ms: u64) -> Option<f32> {
+    let elapsed = time_ms.checked_sub(click.time_ms)?;
+    (elapsed < CLICK_RING_MS).then(|| elapsed as f32 / CLICK_RING_MS as f32)
+}
+
+fn draw_arrow(frame: &mut RgbaImage, cursor: &CursorSample) {
+    let scale = cursor.scale.round().max(1.0) as i64;
+    let (origin_x, origin_y) = (cursor.x.round() as i64, cursor.y.round() as i64);
+    let (width, height) = (frame.width() as i64, frame.height() as i64);
+
+    for (row, line) in ARROW.iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            let color = match c {
+                'X' => Rgba([0, 0, 0, 255]),
+                '.' => Rgba([255, 255, 255, 255]),
+                _ => continue,
+            };
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    let px = origin_x + col as i64 * scale + dx;
+                    let py = origin_y + row as i64 * scale + dy;
+                    if (0..width).contains(&px) && (0..height).contains(&py) {
+                        frame.put_pixel(px as u32, py as u32, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Expanding ring that fades out as it grows
+fn draw_ring(frame: &mut RgbaImage, click: &Click, progress: f32) {
+    let radius = (8.0 + 22.0 * progress) * click.scale;
+    let half_thickness = 1.5 * click.scale;
+    let alpha = 0.85 * (1.0 - progress);
+
+    let reach = radius + half_thickness + 1.0;
+    let x0 = (click.x - reach).floor().max(0.0) as u32;
+    let y0 = (click.y - reach).floor().max(0.0) as u32;
+    let x1 = ((click.x + reach).ceil().max(0.0) as u32).min(frame.width());
+    let y1 = ((click.y + reach).ceil().max(0.0) as u32).min(frame.height());
+
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let dist =
+                ((x as f32 + 0.5 - click.x).powi(2) + (y as f32 + 0.5 - click.y).powi(2)).sqrt();
+            // 1px soft edge on both sides of the stroke
+            let coverage = (half_thickness + 0.5 - (dist - radius).abs()).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                draw::blend_pixel(
+                    frame,
+                    x as i64,
+                    y as i64,
+                    CLICK_RING_COLOR,
+                    alpha * coverage,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Raster helpers for overlays burned into frames (badges, labels, shapes)
+//!
+//! Text uses the bundled DejaVu Sans Bold, so output looks the same on every
+//! machine and no system font lookup is needed.
+
+use std::sync::OnceLock;
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+
+static FONT_DATA: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+
+fn font() -> &'static FontRef<'static> {
+    static FONT: OnceLock<FontRef<'static>> = OnceLock::new();
+    FONT.get_or_init(|| FontRef::try_from_slice(FONT_DATA).expect("bundled font is valid"))
+}
+
+/// Blend `color` over a pixel; `opacity` (e.g. edge coverage) scales its alpha
+pub fn blend_pixel(img: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>, opacity: f32) {
+    if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 {
+        return;
+    }
+    let a = (color[3] as f32 / 255.0 * opacity).clamp(0.0, 1.0);
+    if a <= 0.0 {
+        return;
+    }
+    let pixel = img.get_pixel_mut(x as u32, y as u32);
+    for c in 0..3 {
+        pixel[c] = (pixel[c] as f32 * (1.0 - a) + color[c] as f32 * a).round() as u8;
+    }
+    pixel[3] = (pixel[3] as f32 + (255.0 - pixel[3] as f32) * a).round() as u8;
+}
+
+/// Width and height of a single line of text at `size` px
+pub fn measure_text(text: &str, size: f32) -> (u32, u32) {
+    let scaled = font().as_scaled(PxScale::from(size));
+    let mut width = 0.0;
+    let mut prev = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = prev {
+            width += scaled.kern(prev, id);
+        }
+        width += scaled.h_advance(id);
+        prev = Some(id);
+    }
+    (width.ceil() as u32, scaled.height().ceil() as u32)
+}
+
+/// Draw a single line of text with its top-left corner at (`x`, `y`)
+pub fn draw_text(img: &mut RgbaImage, text: &str, x: f32, y: f32, size: f32, color: Rgba<u8>) {
+    let font = font();
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+    let baseline = y + scaled.ascent();
+
+    let mut caret = x;
+    let mut prev = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = prev {
+            caret += scaled.kern(prev, id);
+        }
+        let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        prev = Some(id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            blend_pixel(
+                img,
+                bounds.min.x as i64 + gx as i64,
+                bounds.min.y as i64 + gy as i64,
+                color,
+                coverage,
+            );
+        });
+    }
+}
+
+/// Fill a rectangle with rounded corners (anti-aliased edges)
+/// Translucency comes from the color's alpha
+pub fn fill_rounded_rect(
+    img: &mut RgbaImage,
+    x: i64,
+    y: i64,
+    width: u32,
+    height: u32,
+    radius: f32,
+    color: Rgba<u8>,
+) {
+    let radius = radius
+        .min(width as f32 / 2.0)
+        .min(height as f32 / 2.0)
+        .max(0.0);
+    let (w, h) = (width as f32, height as f32);
+    for dy in 0..height {
+        for dx in 0..width {
+            // Distance outside the rounded shape, measured from the pixel center
+            let px = dx as f32 + 0.5;
+            let py = dy as f32 + 0.5;
+            let cx = px.clamp(radius, w - radius);
+            let cy = py.clamp(radius, h - radius);
+            let dist = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt() - radius;
+            let coverage = (0.5 - dist).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                blend_pixel(img, x + dx as i64, y + dy as i64, color, coverage);
+            }
+        }
+    }
+}
+
+/// Blend `color` over the pixels in `x0..x1` × `y0..y1` (clipped to the image),
+/// weighted by `coverage` (0.0..=1.0) sampled at each pixel center. Shapes made
+/// of several parts return their combined coverage, so overlaps blend once.
+pub fn fill_coverage(
+    img: &mut RgbaImage,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    color: Rgba<u8>,
+    coverage: impl Fn(f32, f32) -> f32,
+) {
+    let x_start = x0.floor().max(0.0) as u32;
+    let y_start = y0.floor().max(0.0) as u32;
+    let x_end = (x1.ceil().max(0.0) as u32).min(img.width());
+    let y_end = (y1.ceil().max(0.0) as u32).min(img.height());
+    for y in y_start..y_end {
+        for x in x_start..x_end {
+            let c = coverage(x as f32 + 0.5, y as f32 + 0.5).min(1.0);
+            if c > 0.0 {
+                blend_pixel(img, x as i64, y as i64, color, c);
+            }
+        }
+    }
+}
+
+/// Distance from (`px`, `py`) to the segment `a`–`b`
+pub fn segment_distance(px: f32, py: f32, (ax, ay): (f32, f32), (bx, by): (f32, f32)) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((px - ax) * dx + (py - ay) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (px - ax - t * dx).hypot(py - ay - t * dy)
+}
@@ -0,0 +1,307 @@
+//! Non-destructive timeline edits for recordings
+//!
+//! Cuts, duplicates, holds, reversals and speed changes are kept as a list of
+//! operations over the source frames and replayed to get the edited timeline.
+//! Frames on disk are never touched, so every edit can be undone. The list is
+//! saved with the recording's session, so recovery restores it too.
+
+use serde::{Deserialize, Serialize};
+
+use crate::frame_store::FrameSnapshot;
+use crate::types::{Clip, EditOp, EditState};
+
+/// Per-clip playback speed limits. Timelapses go far beyond regular speeds,
+/// e.g. 60× plays one minute of recording per second.
+pub const MIN_SPEED: f32 = 0.1;
+pub const MAX_SPEED: f32 = 1000.0;
+
+/// Longest still a hold can insert
+const MAX_HOLD_MS: u64 = 60_000;
+
+/// Edits of one recording, with undo/redo
+#[derive(Serialize, Deserialize)]
+pub struct EditList {
+    base_speed: f32, // speed of the unedited recording
+    ops: Vec<EditOp>,
+    undone: Vec<EditOp>,
+}
+
+impl EditList {
+    pub fn new(base_speed: f32) -> Self {
+        Self {
+            base_speed: base_speed.clamp(MIN_SPEED, MAX_SPEED),
+            ops: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Apply an edit on top of the current timeline. Invalid edits (e.g. an
+    /// out-of-range selection) are rejected and leave the list unchanged.
+    pub fn apply(&mut self, op: EditOp, source_frames: usize) -> Result<(), String> {
+        self.timeline(source_frames).apply(&op)?;
+        self.ops.push(op);
+        self.undone.clear();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(op) = self.ops.pop() else {
+            return false;
+        };
+        self.undone.push(op);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(op) = self.undone.pop() else {
+            return false;
+        };
+        self.ops.push(op);
+        true
+    }
+
+    /// The edited timeline over a recording of `source_frames` frames
+    pub fn timeline(&self, source_frames: usize) -> Timeline {
+        let mut timeline = Timeline { clips: Vec::new() };
+        if source_frames > 0 {
+            timeline.clips.push(Clip {
+                start: 0,
+                end: source_frames,
+                reversed: false,
+                speed: self.base_speed,
+                hold_ms: None,
+            });
+        }
+        for op in &self.ops {
+            // Every op was validated against the same timeline when it was added
+            let _ = timeline.apply(op);
+        }
+        timeline
+    }
+
+    /// What the editor shows after an edit
+    pub fn state(&self, frames: &FrameSnapshot) -> EditState {
+        let timeline = self.timeline(frames.len());
+        EditState {
+            frame_count: timeline.len(),
+            duration_ms: timeline.output_ms(frames).round() as u64,
+            clips: timeline.clips,
+            can_undo: !self.ops.is_empty(),
+            can_redo: !self.undone.is_empty(),
+        }
+    }
+}
+
+/// The edited sequence of frames, as clips over the source recording
+#[derive(Clone)]
+pub struct Timeline {
+    clips: Vec<Clip>,
+}
+
+impl Timeline {
+    pub fn clips(&self) -> &[Clip] {
+        &self.clips
+    }
+
+    /// Number of frames in the edited timeline
+    pub fn len(&self) -> usize {
+        self.clips.iter().map(|c| c.end - c.start).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+    }
+
+    /// Source frame shown at `index` in the edited timeline
+    pub fn source_index(&self, mut index: usize) -> Option<usize> {
+        for clip in &self.clips {
+            let len = clip.end - clip.start;
+            if index < len {
+                return Some(if clip.reversed {
+                    clip.end - 1 - index
+                } else {
+                    clip.start + index
+                });
+            }
+            index -= len;
+        }
+        None
+    }
+
+    /// Only the edited frames `start..end`
+    pub fn trimmed(&self, start: usize, end: usize) -> Timeline {
+        let mut trimmed = self.clone();
+        let end = end.min(trimmed.len());
+        if start >= end {
+            return Timeline { clips: Vec::new() };
+        }
+        let last = trimmed.split_at(end);
+        trimmed.clips.truncate(last);
+        let first = trimmed.split_at(start);
+        trimmed.clips.drain(..first);
+        trimmed
+    }
+
+    /// Playback duration at each clip's speed
+    pub fn output_ms(&self, frames: &FrameSnapshot) -> f64 {
+        self.clips.iter().map(|c| clip_output_ms(c, frames)).sum()
+    }
+
+    /// Source frame and capture time shown `time_ms` into playback
+    pub fn source_at(&self, frames: &FrameSnapshot, time_ms: f64) -> Option<(usize, u64)> {
+        let mut clip_start_ms = 0.0;
+        for (i, clip) in self.clips.iter().enumerate() {
+            let clip_ms = clip_output_ms(clip, frames);
+            if time_ms < clip_start_ms + clip_ms || i == self.clips.len() - 1 {
+                return Some(clip_source_at(clip, frames, time_ms - clip_start_ms));
+            }
+            clip_start_ms += clip_ms;
+        }
+        None
+    }
+
+    fn apply(&mut self, op: &EditOp) -> Result<(), String> {
+        let len = self.len();
+        let check_range = |start: usize, end: usize| {
+            if start < end && end <= len {
+                Ok(())
+            } else {
+                Err("Invalid frame range".to_string())
+            }
+        };
+        match *op {
+            EditOp::Delete { start, end } => {
+                check_range(start, end)?;
+                if end - start == len {
+                    return Err("Can't delete every frame".to_string());
+                }
+                let (first, last) = self.split_range(start, end);
+                self.clips.drain(first..last);
+            }
+            EditOp::Duplicate { start, end } => {
+                check_range(start, end)?;
+                let (first, last) = self.split_range(start, end);
+                let copy = self.clips[first..last].to_vec();
+                self.clips.splice(last..last, copy);
+            }
+            EditOp::Reverse { start, end } => {
+                check_range(start, end)?;
+                let (first, last) = self.split_range(start, end);
+                self.clips[first..last].reverse();
+                for clip in &mut self.clips[first..last] {
+                    clip.reversed = !clip.reversed;
+                }
+            }
+            EditOp::Speed { start, end, speed } => {
+                check_range(start, end)?;
+                if !speed.is_finite() {
+                    return Err("Invalid speed".to_string());
+                }
+                let (first, last) = self.split_range(start, end);
+                for clip in &mut self.clips[first..last] {
+                    clip.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+                }
+            }
+            EditOp::Hold { frame, duration_ms } => {
+                check_range(frame, frame + 1)?;
+                if duration_ms == 0 {
+                    return Err("Hold duration must be positive".to_string());
+                }
+                let source = self.source_index(frame).unwrap();
+                let at = self.split_at(frame + 1);
+                let speed = self.clips[at - 1].speed;
+                self.clips.insert(
+                    at,
+                    Clip {
+                        start: source,
+                        end: source + 1,
+                        reversed: false,
+                        speed,
+                        hold_ms: Some(duration_ms.min(MAX_HOLD_MS)),
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Split clips so `start` and `end` fall on clip boundaries; returns the clip
+    /// index range covering the edited frames `start..end`
+    fn split_range(&mut self, start: usize, end: usize) -> (usize, usize) {
+        // Start first: splitting at `end` only inserts after the start boundary
+        let first = self.split_at(start);
+        let last = self.split_at(end);
+        (first, last)
+    }
+
+    /// Split the clip containing edited frame `pos` so a clip starts there;
+    /// returns that clip's index (or the clip count if `pos` is the end)
+    fn split_at(&mut self, pos: usize) -> usize {
+        let mut offset = 0;
+        for i in 0..self.clips.len() {
+            let clip = self.clips[i];
+            let len = clip.end - clip.start;
+            if pos == offset {
+                return i;
+            }
+            if pos < offset + len {
+                let k = pos - offset;
+                // Reversed clips show their source range back to front
+                let (first, second) = if clip.reversed {
+                    (
+                        Clip {
+                            start: clip.end - k,
+                            ..clip
+                        },
+                        Clip {
+                            end: clip.end - k,
+                            ..clip
+                        },
+                    )
+                } else {
+                    (
+                        Clip {
+                            end: clip.start + k,
+                            ..clip
+                        },
+                        Clip {
+                            start: clip.start + k,
+                            ..clip
+                        },
+                    )
+                };
+                self.clips[i] = first;
+                self.clips.insert(i + 1, second);
+                return i + 1;
+            }
+            offset += len;
+        }
+        self.clips.len()
+    }
+}
+
+fn clip_output_ms(clip: &Clip, frames: &FrameSnapshot) -> f64 {
+    match clip.hold_ms {
+        Some(hold_ms) => hold_ms as f64,
+        None => frames.span_ms(clip.start, clip.end) as f64 / clip.speed as f64,
+    }
+}
+
+/// Source frame and capture time shown `time_ms` into a clip's playback. Frames
+/// keep their real durations, scaled by the clip's speed.
+fn clip_source_at(clip: &Clip, frames: &FrameSnapshot, time_ms: f64) -> (usize, u64) {
+    if clip.hold_ms.is_some() {
+        return (clip.start, frames.timestamp_ms(clip.start));
+    }
+    let elapsed = (time_ms.max(0.0) * clip.speed as f64).round() as u64;
+    if clip.reversed {
+        // Walk back from the end of the clip's last frame
+        let clip_end_ms = frames.timestamp_ms(clip.start) + frames.span_ms(clip.start, clip.end);
+        let source_ms = clip_end_ms.saturating_sub(elapsed + 1);
+        let index = frames.frame_at(clip.start, clip.end, source_ms);
+        (index, source_ms)
+    } else {
+        let source_ms = frames.timestamp_ms(clip.start) + elapsed;
+        (frames.frame_at(clip.start, clip.end, source_ms), source_ms)
+    }
+}
@@ -0,0 +1,146 @@
+//! Export job manager
+//!
+//! Every export runs as a job with an ID. At most `MAX_RUNNING` jobs encode at
+//! once; further jobs wait in a bounded FIFO queue. Jobs can be cancelled while
+//! queued or running.
+
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Jobs encoding at the same time
+const MAX_RUNNING: usize = 2;
+
+/// Jobs waiting for a free slot
+const MAX_QUEUED: usize = 8;
+
+/// Error message of a job that stopped because it was cancelled
+pub const CANCELLED: &str = "Export cancelled";
+
+/// Cancellation flag shared between a job and the manager
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(CANCELLED)` once cancelled, so long loops can bail out with `?`
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+type Job = Box<dyn FnOnce(u64, CancelToken) + Send>;
+
+#[derive(Default)]
+struct JobQueue {
+    next_id: u64,
+    running: HashMap<u64, CancelToken>,
+    queued: VecDeque<(u64, CancelToken, Job)>,
+}
+
+/// Managed Tauri state; cheap to clone into job threads
+#[derive(Clone, Default)]
+pub struct ExportJobs {
+    inner: Arc<Mutex<JobQueue>>,
+}
+
+impl ExportJobs {
+    /// Queue a job and return its ID. The job receives its ID and cancel token;
+    /// it starts right away if a slot is free.
+    pub fn submit<F>(&self, job: F) -> Result<u64, String>
+    where
+        F: FnOnce(u64, CancelToken) + Send + 'static,
+    {
+        let mut queue = self.inner.lock().unwrap();
+        queue.next_id += 1;
+        let id = queue.next_id;
+        let token = CancelToken::default();
+
+        if queue.running.len() < MAX_RUNNING {
+            queue.running.insert(id, token.clone());
+            drop(queue);
+            println!("[export_jobs] 开始任务 #{}", id);
+            self.spawn(id, token, Box::new(job));
+        } else if queue.queued.len() < MAX_QUEUED {
+            queue.queued.push_back((id, token, Box::new(job)));
+            println!(
+                "[export_jobs] 任务 #{} 排队中 ({} 个等待)",
+                id,
+                queue.queued.len()
+            );
+        } else {
+            return Err("Too many exports queued".to_string());
+        }
+
+        Ok(id)
+    }
+
+    /// Cancel a queued or running job. Returns false if the ID is unknown
+    /// (e.g. the job already finished).
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut queue = self.inner.lock().unwrap();
+
+        if let Some(token) = queue.running.get(&id) {
+            println!("[export_jobs] 取消运行中的任务 #{}", id);
+            token.cancel();
+            return true;
+        }
+
+        let Some(pos) = queue
+            .queued
+            .iter()
+            .position(|(queued_id, _, _)| *queued_id == id)
+        else {
+            return false;
+        };
+        let (_, token, job) = queue.queued.remove(pos).unwrap();
+        drop(queue);
+
+        // Run it outside the slot accounting so it reports the cancellation right away
+        println!("[export_jobs] 取消排队中的任务 #{}", id);
+        token.cancel();
+        thread::spawn(move || job(id, token));
+        true
+    }
+
+    fn spawn(&self, id: u64, token: CancelToken, job: Job) {
+        let jobs = self.clone();
+        thread::spawn(move || {
+            // Free the slot even if the job panics
+            if panic::catch_unwind(AssertUnwindSafe(|| job(id, token))).is_err() {
+                println!("[export_jobs] 任务 #{} 异常退出", id);
+            }
+            jobs.finish(id);
+        });
+    }
+
+    fn finish(&self, id: u64) {
+        let next = {
+            let mut queue = self.inner.lock().unwrap();
+            queue.running.remove(&id);
+            let next = queue.queued.pop_front();
+            if let Some((next_id, token, _)) = &next {
+                queue.running.insert(*next_id, token.clone());
+            }
+            next
+        };
+
+        if let Some((next_id, token, job)) = next {
+            println!("[export_jobs] 开始任务 #{}", next_id);
+            self.spawn(next_id, token, job);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Raw frame export: numbered PNGs in a folder or a ZIP archive
+//!
+//! Both variants include a `manifest.json` with per-frame timestamps and display
+//! times on the output timeline, so the frames can be re-timed in a video editor.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use image::RgbaImage;
+use serde::Serialize;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::animation;
+use crate::frame_source::FrameSource;
+
+const MANIFEST_NAME: &str = "manifest.json";
+
+#[derive(Serialize)]
+struct Manifest {
+    width: u32,
+    height: u32,
+    frame_count: usize,
+    duration_ms: u64,
+    frames: Vec<ManifestFrame>,
+}
+
+#[derive(Serialize)]
+struct ManifestFrame {
+    index: usize,
+    file: String,
+    timestamp_ms: u64,
+    duration_ms: u64,
+}
+
+/// Write frames as `frame_00001.png`, ... plus a manifest into a new folder
+/// Returning an error from `on_progress` aborts the export
+pub fn write_png_sequence<F>(
+    frames: &dyn FrameSource,
+    delays_ms: &[u32],
+    quality: u32,
+    dir: &Path,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    if frames.is_empty() {
+        return Err("No frames to export".to_string());
+    }
+    // Refuse to mix our frames into an existing folder (cancel deletes the whole folder)
+    if dir.exists() {
+        return Err(format!("Output folder already exists: {}", dir.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let manifest = build_manifest(frames, delays_ms);
+    for (i, entry) in manifest.frames.iter().enumerate() {
+        let data = encode_png(&frames.frame(i)?, quality)?;
+        fs::write(dir.join(&entry.file), data).map_err(|e| e.to_string())?;
+        on_progress(i + 1, frames.len())?;
+    }
+
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(dir.join(MANIFEST_NAME), json).map_err(|e| e.to_string())
+}
+
+/// Write the same layout as `write_png_sequence` into a single ZIP archive
+pub fn write_png_zip<F>(
+    frames: &dyn FrameSource,
+    delays_ms: &[u32],
+    quality: u32,
+    path: &Path,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    if frames.is_empty() {
+        return Err("No frames to export".to_string());
+    }
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    // PNG data is already deflated, so store it as-is
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    let manifest = build_manifest(frames, delays_ms);
+    for (i, entry) in manifest.frames.iter().enumerate() {
+        let data = encode_png(&frames.frame(i)?, quality)?;
+        zip.start_file(entry.file.as_str(), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&data).map_err(|e| e.to_string())?;
+        on_progress(i + 1, frames.len())?;
+    }
+
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn build_manifest(frames: &dyn FrameSource, delays_ms: &[u32]) -> Manifest {
+    let (width, height) = frames.dimensions();
+    let mut timestamp_ms = 0;
+    let entries = (0..frames.len())
+        .map(|i| {
+            let duration_ms = delays_ms.get(i).copied().unwrap_or(100) as u64;
+            let entry = ManifestFrame {
+                index: i,
+                file: format!("frame_{:05}.png", i + 1),
+                timestamp_ms,
+                duration_ms,
+            };
+            timestamp_ms += duration_ms;
+            entry
+        })
+        .collect();
+
+    Manifest {
+        width,
+        height,
+        frame_count: frames.len(),
+        duration_ms: timestamp_ms,
+        frames: entries,
+    }
+}
+
+fn encode_png(frame: &RgbaImage, quality: u32) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, frame.width(), frame.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(animation::png_compression(quality));
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(frame.as_raw())
+        .map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(data)
+}
@@ -0,0 +1,23 @@
+//! Frames handed to the encoders
+//!
+//! Exports can be thousands of frames, too many to keep decoded at once, so
+//! encoders pull each frame from a `FrameSource` when they get to it and drop it
+//! once it is encoded.
+
+use image::RgbaImage;
+
+/// Output frames of an export, rendered on demand
+pub trait FrameSource: Sync {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size of every frame
+    fn dimensions(&self) -> (u32, u32);
+
+    /// Render frame `index`. May be called from several threads at once, in
+    /// any order, and more than once for the same frame.
+    fn frame(&self, index: usize) -> Result<RgbaImage, String>;
+}
@@ -0,0 +1,832 @@
+//! Disk-backed frame storage for recordings
+//!
+//! Frames are LZ4-compressed and written to a session directory as they are
+//! captured. Only a small window of the most recent frames is kept in memory, so
+//! long recordings no longer grow RAM usage linearly.
+//!
+//! Each frame carries its capture time in ms since the recording started. Capture
+//! can fall behind the target fps, so durations come from these timestamps rather
+//! than from frame counts. The pointer position sampled with each frame and any
+//! logged key presses are kept alongside, so they can be drawn in at export time.
+//!
+//! Everything needed to reopen a recording is persisted incrementally, so a crash
+//! or force quit doesn't lose it:
+//!
+//! - `session.json`: region, fps and mode (`SessionManifest`)
+//! - `frames.jsonl`: one line per frame with its timestamp and cursor sample,
+//!   appended after the frame file is written
+//! - `keys.json`: the key log
+//! - `annotations.json`: captions and shapes added in the editor
+//! - `redactions.json`: areas to scrub that aren't burned into the frames yet
+//! - `edits.json`: the timeline edits and their undo history
+//! - `000000.lz4`, ...: the frames
+//!
+//! Redactions stay editable in the editor, but the pixels they hide must not
+//! survive on disk. They're burned into the frame files when an export starts,
+//! when the editor closes, and at launch for sessions left by a crash.
+//!
+//! The directory is deleted when the store is cleared or dropped, unless it was
+//! released unexported (its editor closed). A session still on disk at the next
+//! launch was never exported and can be recovered.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use image::RgbaImage;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::cursor::CursorSample;
+use crate::edit_list::EditList;
+use crate::redact;
+use crate::types::{
+    CaptureMode, KeyEvent, RecoverableSession, Region, TimedAnnotation, TimedRedaction,
+    TimelapseOptions,
+};
+
+/// Number of most recent frames kept decoded in memory
+const MEMORY_WINDOW: usize = 8;
+
+/// Unexported sessions older than this are deleted instead of offered for recovery
+const SESSION_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const MANIFEST_FILE: &str = "session.json";
+const FRAME_LOG_FILE: &str = "frames.jsonl";
+const KEYS_FILE: &str = "keys.json";
+const ANNOTATIONS_FILE: &str = "annotations.json";
+const REDACTIONS_FILE: &str = "redactions.json";
+const EDITS_FILE: &str = "edits.json";
+
+fn sessions_root() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("lovshot")
+        .join("sessions")
+}
+
+/// Identifies this run of the app in manifests; unlike the pid it's never reused
+fn run_id() -> u64 {
+    static RUN_ID: OnceLock<u64> = OnceLock::new();
+    *RUN_ID.get_or_init(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64
+    })
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Session IDs come from the frontend, so only accept our own directory names
+fn session_path(id: &str) -> Result<PathBuf, String> {
+    let valid =
+        id.starts_with("session-") && id[8..].chars().all(|c| c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err("Invalid session id".to_string());
+    }
+    Ok(sessions_root().join(id))
+}
+
+/// How a recording was made, written when it starts
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub run_id: u64,     // run of the app that owns the session; others leave it alone
+    pub created_ms: u64, // unix time
+    pub mode: CaptureMode,
+    pub region: Region,
+    pub fps: u32,
+    pub timelapse: Option<TimelapseOptions>,
+    #[serde(default)]
+    pub end_ms: Option<u64>, // set when the recording stops normally
+    #[serde(default)]
+    pub exported: bool,
+}
+
+impl SessionManifest {
+    pub fn new(
+        mode: CaptureMode,
+        region: Region,
+        fps: u32,
+        timelapse: Option<TimelapseOptions>,
+    ) -> Self {
+        Self {
+            run_id: run_id(),
+            created_ms: now_ms(),
+            mode,
+            region,
+            fps,
+            timelapse,
+            end_ms: None,
+            exported: false,
+        }
+    }
+}
+
+/// One line of `frames.jsonl`
+#[derive(Serialize, Deserialize)]
+struct FrameRecord {
+    timestamp_ms: u64,
+    cursor: Option<CursorSample>,
+}
+
+/// Session directory on disk, removed when the last user drops it
+struct SessionDir {
+    path: PathBuf,
+    manifest: Mutex<SessionManifest>,
+    /// Pending redactions, shared by the store and its snapshots so a burn
+    /// takes effect everywhere at once
+    redactions: Mutex<Vec<TimedRedaction>>,
+    keep: AtomicBool, // released unexported; leave it for recovery
+}
+
+impl SessionDir {
+    fn create(manifest: SessionManifest) -> Result<Self, String> {
+        let path = sessions_root().join(format!(
+            "session-{}-{}",
+            manifest.created_ms,
+            std::process::id()
+        ));
+        fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+        let dir = Self {
+            path,
+            manifest: Mutex::new(manifest),
+            redactions: Mutex::default(),
+            keep: AtomicBool::new(false),
+        };
+        dir.update_manifest(|_| {})?;
+        Ok(dir)
+    }
+
+    fn frame_path(&self, index: usize) -> PathBuf {
+        frame_path(&self.path, index)
+    }
+
+    /// Apply `f` and rewrite `session.json` (via a temp file, so it's never torn)
+    fn update_manifest(&self, f: impl FnOnce(&mut SessionManifest)) -> Result<(), String> {
+        let mut manifest = self.manifest.lock().unwrap();
+        f(&mut manifest);
+        let json = serde_json::to_vec_pretty(&*manifest).map_err(|e| e.to_string())?;
+        let tmp = self.path.join(format!("{}.tmp", MANIFEST_FILE));
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, self.path.join(MANIFEST_FILE)).map_err(|e| e.to_string())
+    }
+
+    fn write_keystrokes(&self, events: &[KeyEvent]) {
+        if let Err(e) = self.write_json(KEYS_FILE, events) {
+            println!("[frame_store] 保存按键记录失败: {}", e);
+        }
+    }
+
+    fn write_annotations(&self, annotations: &[TimedAnnotation]) {
+        if let Err(e) = self.write_json(ANNOTATIONS_FILE, annotations) {
+            println!("[frame_store] 保存标注失败: {}", e);
+        }
+    }
+
+    fn redactions(&self) -> Vec<TimedRedaction> {
+        self.redactions.lock().unwrap().clone()
+    }
+
+    fn set_redactions(&self, redactions: Vec<TimedRedaction>) {
+        let mut pending = self.redactions.lock().unwrap();
+        if let Err(e) = self.write_json(REDACTIONS_FILE, &redactions) {
+            println!("[frame_store] 保存打码区域失败: {}", e);
+        }
+        *pending = redactions;
+    }
+
+    /// Burn the pending redactions into the first `frame_count` frames and clear
+    /// them. Holds the list meanwhile, so nothing reads a half-burned recording
+    /// as still needing them.
+    fn burn_redactions(&self, frame_count: usize) -> Result<(), String> {
+        let mut pending = self.redactions.lock().unwrap();
+        if pending.is_empty() {
+            return Ok(());
+        }
+        burn_redactions(&self.path, &pending, frame_count)?;
+        pending.clear();
+        self.write_json(REDACTIONS_FILE, &*pending)
+    }
+
+    /// Replace `file` via a rename, so a crash mid-write keeps the old version
+    fn write_json<T: Serialize + ?Sized>(&self, file: &str, value: &T) -> Result<(), String> {
+        let json = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+        let tmp = self.path.join(format!("{}.tmp", file));
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, self.path.join(file)).map_err(|e| e.to_string())
+    }
+}
+
+impl Drop for SessionDir {
+    fn drop(&mut self) {
+        if self.keep.load(Ordering::Relaxed) && !self.manifest.lock().unwrap().exported {
+            return;
+        }
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn frame_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{:06}.lz4", index))
+}
+
+/// Rewrite every frame file a redaction covers with the redaction applied.
+/// Frames are replaced via a rename, so readers see either version whole.
+fn burn_redactions(
+    dir: &Path,
+    redactions: &[TimedRedaction],
+    frame_count: usize,
+) -> Result<(), String> {
+    let mut indices: Vec<usize> = redactions
+        .iter()
+        .flat_map(|r| r.start_frame..r.end_frame.min(frame_count))
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    println!(
+        "[frame_store] 写入打码: {} 个区域, {} 帧",
+        redactions.len(),
+        indices.len()
+    );
+    for index in indices {
+        let path = frame_path(dir, index);
+        let mut frame = decompress(&fs::read(&path).map_err(|e| e.to_string())?)?;
+        redact::apply_for_frame(&mut frame, redactions, index);
+        let tmp = path.with_extension("lz4.tmp");
+        fs::write(&tmp, FrameStore::compress(&frame)).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn read_manifest(path: &Path) -> Option<SessionManifest> {
+    read_json(&path.join(MANIFEST_FILE))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let data = fs::read(path).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Frame records whose frame file made it to disk. A crash can leave a torn last
+/// line or a missing frame; everything from there on is dropped.
+fn read_frame_log(path: &Path) -> Vec<FrameRecord> {
+    let Ok(log) = fs::read_to_string(path.join(FRAME_LOG_FILE)) else {
+        return Vec::new();
+    };
+    let mut records = Vec::new();
+    for line in log.lines() {
+        let Ok(record) = serde_json::from_str::<FrameRecord>(line) else {
+            break;
+        };
+        if !frame_path(path, records.len()).exists() {
+            break;
+        }
+        records.push(record);
+    }
+    records
+}
+
+/// Length of a recovered recording: until the stop if it was recorded, otherwise
+/// one frame interval past the last frame
+fn recovered_end_ms(manifest: &SessionManifest, records: &[FrameRecord]) -> u64 {
+    let last = records.last().map_or(0, |r| r.timestamp_ms);
+    let interval = manifest
+        .timelapse
+        .map_or(1000 / manifest.fps.max(1) as u64, |t| t.interval_ms);
+    manifest.end_ms.unwrap_or(last + interval).max(last)
+}
+
+/// Delete sessions left behind by previous runs that can't or shouldn't be
+/// recovered: exported, empty, unreadable or past the retention period. Also
+/// removes frame spools from versions that kept them in the temp directory.
+pub fn cleanup_sessions() {
+    if let Ok(entries) = fs::read_dir(std::env::temp_dir().join("lovshot")) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("spool-") {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(sessions_root()) else {
+        return;
+    };
+    let now = now_ms();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let manifest = read_manifest(&path);
+        if manifest.as_ref().is_some_and(|m| m.run_id == run_id()) {
+            continue;
+        }
+        let keep = manifest.is_some_and(|m| {
+            !m.exported
+                && now.saturating_sub(m.created_ms) < SESSION_RETENTION.as_millis() as u64
+                && frame_path(&path, 0).exists()
+        });
+        if !keep {
+            println!(
+                "[frame_store] 清理过期录制: {}",
+                entry.file_name().to_string_lossy()
+            );
+            let _ = fs::remove_dir_all(&path);
+            continue;
+        }
+
+        // Redactions still pending (the app quit before burning them)
+        let redactions: Vec<TimedRedaction> =
+            read_json(&path.join(REDACTIONS_FILE)).unwrap_or_default();
+        if !redactions.is_empty() {
+            let frame_count = read_frame_log(&path).len();
+            match burn_redactions(&path, &redactions, frame_count) {
+                Ok(()) => {
+                    let _ = fs::write(path.join(REDACTIONS_FILE), "[]");
+                }
+                Err(e) => println!("[frame_store] 写入打码失败: {}", e),
+            }
+        }
+    }
+}
+
+/// Unexported sessions not open in an editor (left by a previous run, or
+/// released when their editor closed), newest first
+pub fn recoverable_sessions() -> Vec<RecoverableSession> {
+    let Ok(entries) = fs::read_dir(sessions_root()) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<RecoverableSession> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let manifest = read_manifest(&path)?;
+            if manifest.run_id == run_id() || manifest.exported {
+                return None;
+            }
+            let records = read_frame_log(&path);
+            if records.is_empty() {
+                return None;
+            }
+            Some(RecoverableSession {
+                id: entry.file_name().to_string_lossy().to_string(),
+                created_ms: manifest.created_ms,
+                mode: manifest.mode,
+                frame_count: records.len(),
+                duration_ms: recovered_end_ms(&manifest, &records),
+            })
+        })
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.created_ms));
+    sessions
+}
+
+/// Delete a session from a previous run
+pub fn remove_session(id: &str) -> Result<(), String> {
+    let path = session_path(id)?;
+    if read_manifest(&path).is_some_and(|m| m.run_id == run_id()) {
+        return Err("Session is in use".to_string());
+    }
+    fs::remove_dir_all(&path).map_err(|e| e.to_string())
+}
+
+/// Frame store behind `AppState.frames`
+#[derive(Default)]
+pub struct FrameStore {
+    session: Option<Arc<SessionDir>>,
+    frame_log: Option<File>,
+    count: usize,
+    dimensions: Option<(u32, u32)>,
+    window: VecDeque<(usize, RgbaImage)>,
+    /// Shared with snapshots; only copied on push while an export holds one
+    timestamps: Arc<Vec<u64>>,
+    cursor: Arc<Vec<Option<CursorSample>>>,
+    keystrokes: Arc<Vec<KeyEvent>>,
+    annotations: Arc<Vec<TimedAnnotation>>,
+    end_ms: u64,
+    stored_bytes: u64,
+}
+
+impl FrameStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compress a frame for writing to disk. This is the expensive part of `push_compressed`,
+    /// so callers should run it without holding the state lock.
+    pub fn compress(frame: &RgbaImage) -> Vec<u8> {
+        let (w, h) = frame.dimensions();
+        let mut data = Vec::with_capacity(8 + frame.as_raw().len() / 2);
+        data.extend_from_slice(&w.to_le_bytes());
+        data.extend_from_slice(&h.to_le_bytes());
+        data.extend_from_slice(&lz4_flex::compress_prepend_size(frame.as_raw()));
+        data
+    }
+
+    /// Drop any previous recording and create the session directory for a new one
+    pub fn start_session(&mut self, manifest: SessionManifest) -> Result<(), String> {
+        self.clear();
+        let session = SessionDir::create(manifest)?;
+        let frame_log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(session.path.join(FRAME_LOG_FILE))
+            .map_err(|e| e.to_string())?;
+        println!("[frame_store] 新建录制会话: {}", session.path.display());
+        self.session = Some(Arc::new(session));
+        self.frame_log = Some(frame_log);
+        Ok(())
+    }
+
+    /// Reopen a session left behind by a previous run and take ownership of it
+    pub fn open_session(id: &str) -> Result<(Self, SessionManifest), String> {
+        let path = session_path(id)?;
+        let manifest = read_manifest(&path).ok_or("Recording session not found")?;
+        if manifest.run_id == run_id() {
+            return Err("Session is in use".to_string());
+        }
+        let records = read_frame_log(&path);
+        if records.is_empty() {
+            return Err("Recording session has no frames".to_string());
+        }
+        let first = fs::read(frame_path(&path, 0)).map_err(|e| e.to_string())?;
+        let dimensions = decompress(&first)?.dimensions();
+        let stored_bytes = (0..records.len())
+            .filter_map(|i| fs::metadata(frame_path(&path, i)).ok())
+            .map(|m| m.len())
+            .sum();
+        let keystrokes: Vec<KeyEvent> = read_json(&path.join(KEYS_FILE)).unwrap_or_default();
+        let annotations: Vec<TimedAnnotation> =
+            read_json(&path.join(ANNOTATIONS_FILE)).unwrap_or_default();
+        let redactions: Vec<TimedRedaction> =
+            read_json(&path.join(REDACTIONS_FILE)).unwrap_or_default();
+        let end_ms = recovered_end_ms(&manifest, &records);
+
+        let session = SessionDir {
+            path,
+            manifest: Mutex::new(manifest),
+            redactions: Mutex::new(redactions),
+            keep: AtomicBool::new(false),
+        };
+        session.update_manifest(|m| {
+            m.run_id = run_id();
+            m.end_ms = Some(end_ms);
+        })?;
+        let manifest = session.manifest.lock().unwrap().clone();
+        println!(
+            "[frame_store] 恢复录制会话: {} ({} 帧)",
+            session.path.display(),
+            records.len()
+        );
+
+        let mut store = Self::new();
+        store.session = Some(Arc::new(session));
+        store.count = records.len();
+        store.dimensions = Some(dimensions);
+        store.timestamps = Arc::new(records.iter().map(|r| r.timestamp_ms).collect());
+        store.cursor = Arc::new(records.iter().map(|r| r.cursor).collect());
+        store.keystrokes = Arc::new(keystrokes);
+        store.annotations = Arc::new(annotations);
+        store.end_ms = end_ms;
+        store.stored_bytes = stored_bytes;
+        Ok((store, manifest))
+    }
+
+    /// Name of the session directory, used as the session ID
+    pub fn session_id(&self) -> Option<String> {
+        let session = self.session.as_ref()?;
+        let name = session.path.file_name()?;
+        Some(name.to_string_lossy().to_string())
+    }
+
+    /// Unload the recording (e.g. its editor closed). If it wasn't exported it
+    /// stays on disk and can be recovered later; otherwise it's deleted once no
+    /// snapshot references it. Pending redactions are burned in first, on a
+    /// background thread.
+    pub fn release(self) {
+        let Some(session) = self.session else {
+            return;
+        };
+        session.keep.store(true, Ordering::Relaxed);
+        let frame_count = self.count;
+        std::thread::spawn(move || {
+            if let Err(e) = session.burn_redactions(frame_count) {
+                println!("[frame_store] 写入打码失败: {}", e);
+            }
+            // Hand it over to recovery; run IDs start from a nonzero timestamp
+            if let Err(e) = session.update_manifest(|m| m.run_id = 0) {
+                println!("[frame_store] 更新会话清单失败: {}", e);
+            }
+        });
+    }
+
+    /// Append a frame along with its compressed form from `compress`
+    /// `timestamp_ms` is the capture time relative to the start of the recording
+    pub fn push_compressed(
+        &mut self,
+        frame: RgbaImage,
+        compressed: &[u8],
+        timestamp_ms: u64,
+        cursor: Option<CursorSample>,
+    ) -> Result<(), String> {
+        let session = self
+            .session
+            .as_ref()
+            .ok_or("Recording session not started")?;
+        fs::write(session.frame_path(self.count), compressed).map_err(|e| e.to_string())?;
+        // The log line goes last, so a logged frame is always complete on disk
+        if let Some(log) = self.frame_log.as_mut() {
+            let record = FrameRecord {
+                timestamp_ms,
+                cursor,
+            };
+            let line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+            writeln!(log, "{}", line).map_err(|e| e.to_string())?;
+        }
+        self.stored_bytes += compressed.len() as u64;
+
+        if self.dimensions.is_none() {
+            self.dimensions = Some(frame.dimensions());
+        }
+        self.window.push_back((self.count, frame));
+        while self.window.len() > MEMORY_WINDOW {
+            self.window.pop_front();
+        }
+        Arc::make_mut(&mut self.timestamps).push(timestamp_ms);
+        Arc::make_mut(&mut self.cursor).push(cursor);
+        self.end_ms = self.end_ms.max(timestamp_ms);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Log key presses seen while recording
+    pub fn push_keystrokes(&mut self, events: Vec<KeyEvent>) {
+        if !events.is_empty() {
+            Arc::make_mut(&mut self.keystrokes).extend(events);
+            if let Some(session) = &self.session {
+                session.write_keystrokes(&self.keystrokes);
+            }
+        }
+    }
+
+    pub fn keystrokes(&self) -> &[KeyEvent] {
+        &self.keystrokes
+    }
+
+    /// Replace the key log (edited in the editor), kept sorted by time
+    pub fn set_keystrokes(&mut self, mut events: Vec<KeyEvent>) {
+        events.sort_by_key(|e| e.timestamp_ms);
+        if let Some(session) = &self.session {
+            session.write_keystrokes(&events);
+        }
+        self.keystrokes = Arc::new(events);
+    }
+
+    pub fn annotations(&self) -> &[TimedAnnotation] {
+        &self.annotations
+    }
+
+    /// Replace the captions and shapes drawn on export
+    pub fn set_annotations(&mut self, annotations: Vec<TimedAnnotation>) {
+        if let Some(session) = &self.session {
+            session.write_annotations(&annotations);
+        }
+        self.annotations = Arc::new(annotations);
+    }
+
+    /// Redactions not yet burned into the frames
+    pub fn redactions(&self) -> Vec<TimedRedaction> {
+        self.session
+            .as_ref()
+            .map(|s| s.redactions())
+            .unwrap_or_default()
+    }
+
+    /// Replace the pending redactions
+    pub fn set_redactions(&mut self, redactions: Vec<TimedRedaction>) {
+        if let Some(session) = &self.session {
+            session.set_redactions(redactions);
+        }
+    }
+
+    /// Save the timeline edits with the session
+    pub fn save_edits(&self, edits: &EditList) {
+        if let Some(session) = &self.session {
+            if let Err(e) = session.write_json(EDITS_FILE, edits) {
+                println!("[frame_store] 保存剪辑记录失败: {}", e);
+            }
+        }
+    }
+
+    /// Timeline edits saved with the session (see `save_edits`), if any
+    pub fn saved_edits(&self) -> Option<EditList> {
+        read_json(&self.session.as_ref()?.path.join(EDITS_FILE))
+    }
+
+    /// Record when the recording stopped, so the last frame gets its real duration
+    pub fn finish(&mut self, end_ms: u64) {
+        self.end_ms = self.end_ms.max(end_ms);
+        self.frame_log = None;
+        // Frames on disk are the source of truth from now on (redactions rewrite them)
+        self.window.clear();
+        if let Some(session) = &self.session {
+            let end_ms = self.end_ms;
+            if let Err(e) = session.update_manifest(|m| m.end_ms = Some(end_ms)) {
+                println!("[frame_store] 更新会话清单失败: {}", e);
+            }
+        }
+    }
+
+    /// Read a frame, from the in-memory window if possible, otherwise from disk
+    pub fn get(&self, index: usize) -> Result<RgbaImage, String> {
+        if let Some((_, frame)) = self.window.iter().find(|(i, _)| *i == index) {
+            return Ok(frame.clone());
+        }
+        self.snapshot().get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Dimensions of the first frame, if any
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        self.dimensions
+    }
+
+    /// Wall-clock length of the recording
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms
+    }
+
+    /// Total size of the compressed frames written to disk
+    pub fn stored_bytes(&self) -> u64 {
+        self.stored_bytes
+    }
+
+    /// Drop all frames. The session directory is deleted once no snapshot
+    /// references it.
+    pub fn clear(&mut self) {
+        self.session = None;
+        self.frame_log = None;
+        self.count = 0;
+        self.dimensions = None;
+        self.window.clear();
+        self.timestamps = Arc::default();
+        self.cursor = Arc::default();
+        self.keystrokes = Arc::default();
+        self.annotations = Arc::default();
+        self.end_ms = 0;
+        self.stored_bytes = 0;
+    }
+
+    /// Cheap read-only view for background work (e.g. export threads) that must not
+    /// hold the state lock. It stays valid even if the store is cleared meanwhile.
+    pub fn snapshot(&self) -> FrameSnapshot {
+        FrameSnapshot {
+            session: self.session.clone(),
+            count: self.count,
+            dimensions: self.dimensions,
+            timestamps: self.timestamps.clone(),
+            cursor: self.cursor.clone(),
+            keystrokes: self.keystrokes.clone(),
+            annotations: self.annotations.clone(),
+            end_ms: self.end_ms,
+        }
+    }
+}
+
+/// Read-only view of a `FrameStore` at a point in time
+#[derive(Clone)]
+pub struct FrameSnapshot {
+    session: Option<Arc<SessionDir>>,
+    count: usize,
+    dimensions: Option<(u32, u32)>,
+    timestamps: Arc<Vec<u64>>,
+    cursor: Arc<Vec<Option<CursorSample>>>,
+    keystrokes: Arc<Vec<KeyEvent>>,
+    annotations: Arc<Vec<TimedAnnotation>>,
+    end_ms: u64,
+}
+
+impl FrameSnapshot {
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Dimensions of the first frame, if any
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        self.dimensions
+    }
+
+    /// Wall-clock length of the recording
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms
+    }
+
+    /// Capture time of a frame in ms since the recording started
+    pub fn timestamp_ms(&self, index: usize) -> u64 {
+        self.timestamps.get(index).copied().unwrap_or(self.end_ms)
+    }
+
+    /// Wall-clock time covered by frames `start..end`
+    pub fn span_ms(&self, start: usize, end: usize) -> u64 {
+        span_ms(&self.timestamps, self.end_ms, start, end)
+    }
+
+    /// Pointer state per frame (None if it couldn't be read)
+    pub fn cursor_samples(&self) -> &[Option<CursorSample>] {
+        &self.cursor
+    }
+
+    pub fn keystrokes(&self) -> &[KeyEvent] {
+        &self.keystrokes
+    }
+
+    pub fn annotations(&self) -> &[TimedAnnotation] {
+        &self.annotations
+    }
+
+    /// Redactions not yet burned into the frames
+    pub fn redactions(&self) -> Vec<TimedRedaction> {
+        self.session
+            .as_ref()
+            .map(|s| s.redactions())
+            .unwrap_or_default()
+    }
+
+    /// Burn the pending redactions into the frame files (exports call this
+    /// first, so the redacted pixels don't outlive them in the session)
+    pub fn burn_redactions(&self) -> Result<(), String> {
+        match &self.session {
+            Some(session) => session.burn_redactions(self.count),
+            None => Ok(()),
+        }
+    }
+
+    /// Note that the recording was exported, so it isn't offered for recovery
+    pub fn mark_exported(&self) {
+        if let Some(session) = &self.session {
+            if let Err(e) = session.update_manifest(|m| m.exported = true) {
+                println!("[frame_store] 更新会话清单失败: {}", e);
+            }
+        }
+    }
+
+    /// Last frame in `start..end` captured at or before `time_ms` (or `start`)
+    pub fn frame_at(&self, start: usize, end: usize, time_ms: u64) -> usize {
+        let end = end.min(self.timestamps.len());
+        if start >= end {
+            return start;
+        }
+        let after = self.timestamps[start..end].partition_point(|&t| t <= time_ms);
+        start + after.saturating_sub(1)
+    }
+
+    /// Load and decompress a frame from disk
+    pub fn get(&self, index: usize) -> Result<RgbaImage, String> {
+        if index >= self.count {
+            return Err("Frame index out of bounds".to_string());
+        }
+        let session = self.session.as_ref().ok_or("No frames available")?;
+        let data = fs::read(session.frame_path(index)).map_err(|e| e.to_string())?;
+        decompress(&data)
+    }
+}
+
+/// A range runs from its first frame's capture to the next frame's capture
+/// (or the end of the recording)
+fn span_ms(timestamps: &[u64], end_ms: u64, start: usize, end: usize) -> u64 {
+    let end = end.min(timestamps.len());
+    if start >= end {
+        return 0;
+    }
+    let range_end = timestamps.get(end).copied().unwrap_or(end_ms);
+    range_end.saturating_sub(timestamps[start])
+}
+
+fn decompress(data: &[u8]) -> Result<RgbaImage, String> {
+    if data.len() < 8 {
+        return Err("Corrupted frame file".to_string());
+    }
+    let w = u32::from_le_bytes(data[0..4].try_into().unwrap());
+    let h = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    let raw = lz4_flex::decompress_size_prepended(&data[8..]).map_err(|e| e.to_string())?;
+    RgbaImage::from_raw(w, h, raw).ok_or_else(|| "Corrupted frame file".to_string())
+}
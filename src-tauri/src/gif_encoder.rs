@@ -0,0 +1,396 @@
+//! GIF encoding with inter-frame optimization
+//!
+//! Screen recordings mostly change in small areas (cursor, typing), so after the
+//! first frame only the changed bounding box is written. Unchanged pixels inside
+//! that box are made transparent, letting the previous frame show through, and
+//! byte-identical consecutive frames are merged into one longer frame.
+//!
+//! Palettes come from `quantize`, either per frame or one global table shared by
+//! all frames (no palette flicker between frames).
+//!
+//! Frames are loaded, quantized and LZW-compressed on a pool of worker threads
+//! and written to the file in order. Only a bounded window of frames is in
+//! flight, and each worker loads just the frame it encodes (plus the previous
+//! one to diff against), so the window also bounds how many frames are decoded.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use gif::{DisposalMethod, Encoder, Frame, Repeat};
+use image::RgbaImage;
+
+use crate::frame_source::FrameSource;
+use crate::quantize::Palette;
+use crate::types::{ExportConfig, GifLoopMode, GifPalette};
+
+/// Maximum GIF frame delay (1/100 s units stored as u16)
+const MAX_DELAY: u16 = u16::MAX;
+
+/// Palette colors per table; the 256th entry is reserved for transparency
+const MAX_COLORS: usize = 255;
+
+/// Frames sampled to build a global palette
+const GLOBAL_PALETTE_SAMPLES: usize = 16;
+
+/// Changed region of a frame relative to the previous one
+#[derive(Clone, Copy)]
+struct Bounds {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Encode frames as GIF, each shown for its entry in `delays_ms`
+/// Returning an error from `on_progress` aborts encoding
+pub fn encode_gif<F>(
+    frames: &dyn FrameSource,
+    delays_ms: &[u32],
+    config: &ExportConfig,
+    loop_mode: &GifLoopMode,
+    path: &Path,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    if frames.is_empty() {
+        return Err("No frames to encode".to_string());
+    }
+
+    let (width, height) = frames.dimensions();
+    let frame_count = frames.len();
+    let delays = to_centiseconds(delays_ms, frame_count);
+
+    // A shared palette goes into the global color table; index `len` is transparent
+    let global = match config.palette {
+        GifPalette::Global => {
+            let palette = build_global_palette(frames, config)?;
+            println!("[DEBUG][gif_encoder] 全局调色板: {} 色", palette.len());
+            Some(palette)
+        }
+        GifPalette::PerFrame => None,
+    };
+    let global_table = global
+        .as_ref()
+        .map(|p| with_transparent_entry(p.to_rgb_bytes()));
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = Encoder::new(
+        BufWriter::new(file),
+        width as u16,
+        height as u16,
+        global_table.as_deref().unwrap_or(&[]),
+    )
+    .map_err(|e| e.to_string())?;
+
+    let repeat = match loop_mode {
+        GifLoopMode::Once => Repeat::Finite(0),
+        _ => Repeat::Infinite,
+    };
+    encoder.set_repeat(repeat).map_err(|e| e.to_string())?;
+
+    let cores = thread::available_parallelism().map_or(4, |n| n.get());
+    let window = config.max_in_flight_frames.unwrap_or(cores * 2).max(1);
+    let workers = cores.min(window).min(frame_count);
+    println!(
+        "[DEBUG][gif_encoder] 并行编码: {} 线程, 窗口 {} 帧",
+        workers, window
+    );
+
+    let (job_tx, job_rx) = mpsc::channel::<usize>();
+    let job_rx = Mutex::new(job_rx);
+    let (done_tx, done_rx) = mpsc::channel::<(usize, Result<(Frame<'static>, bool), String>)>();
+
+    thread::scope(|scope| -> Result<(), String> {
+        // Owned by this closure so an early return hangs up on the workers
+        let job_tx = job_tx;
+
+        for _ in 0..workers {
+            let job_rx = &job_rx;
+            let delays = &delays;
+            let done_tx = done_tx.clone();
+            // Each worker gets its own copy so nearest-color caches don't contend
+            let mut palette = global.clone();
+            scope.spawn(move || loop {
+                let Ok(index) = job_rx.lock().unwrap().recv() else {
+                    break;
+                };
+                let frame = panic::catch_unwind(AssertUnwindSafe(|| {
+                    encode_frame(frames, index, delays[index], palette.as_mut(), config)
+                }))
+                .unwrap_or_else(|_| Err("GIF frame encoding panicked".to_string()));
+                if done_tx.send((index, frame)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(done_tx);
+
+        // At most `window` frames are dispatched but not yet written
+        let mut next_dispatch = 0;
+        while next_dispatch < frame_count.min(window) {
+            let _ = job_tx.send(next_dispatch);
+            next_dispatch += 1;
+        }
+
+        // The last frame is held back until the next one arrives, so frames
+        // identical to it can be merged into one longer frame
+        let mut pending: Option<Frame<'static>> = None;
+        let mut written = 0;
+        let mut ready = BTreeMap::new();
+        let mut next_write = 0;
+        while next_write < frame_count {
+            let (index, frame) = done_rx
+                .recv()
+                .map_err(|_| "GIF encoder workers stopped".to_string())?;
+            ready.insert(index, frame?);
+
+            while let Some((frame, unchanged)) = ready.remove(&next_write) {
+                match &mut pending {
+                    Some(last) if unchanged && last.delay <= MAX_DELAY - frame.delay => {
+                        last.delay += frame.delay
+                    }
+                    _ => {
+                        if let Some(last) = pending.replace(frame) {
+                            encoder
+                                .write_lzw_pre_encoded_frame(&last)
+                                .map_err(|e| e.to_string())?;
+                            written += 1;
+                        }
+                    }
+                }
+                next_write += 1;
+                on_progress(next_write, frame_count)?;
+
+                if next_write == 1 || next_write % 10 == 0 || next_write == frame_count {
+                    println!("[DEBUG][gif_encoder] 编码帧 {}/{}", next_write, frame_count);
+                }
+
+                if next_dispatch < frame_count {
+                    let _ = job_tx.send(next_dispatch);
+                    next_dispatch += 1;
+                }
+            }
+        }
+        if let Some(last) = pending {
+            encoder
+                .write_lzw_pre_encoded_frame(&last)
+                .map_err(|e| e.to_string())?;
+            written += 1;
+        }
+        println!("[DEBUG][gif_encoder] 合并相同帧后: {} 帧", written);
+
+        Ok(())
+    })
+}
+
+/// Load, delta, quantize and LZW-compress one frame; runs on a worker thread.
+/// Also tells whether the frame is identical to the previous one.
+fn encode_frame(
+    frames: &dyn FrameSource,
+    i: usize,
+    frame_delay: u16,
+    global: Option<&mut Palette>,
+    config: &ExportConfig,
+) -> Result<(Frame<'static>, bool), String> {
+    let curr = frames.frame(i)?;
+    let (bounds, pixels, unchanged) = if i == 0 {
+        (full_bounds(&curr), curr.into_raw(), false)
+    } else {
+        let prev = frames.frame(i - 1)?;
+        // Identical frames are only written when merging would overflow the
+        // delay; a single transparent pixel just extends the display time
+        let changed = changed_bounds(&prev, &curr);
+        let bounds = changed.unwrap_or(Bounds {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        });
+        (
+            bounds,
+            delta_pixels(&prev, &curr, bounds),
+            changed.is_none(),
+        )
+    };
+
+    let origin = (bounds.x, bounds.y);
+    let mut frame = match global {
+        Some(palette) => {
+            let transparent = palette.len() as u8;
+            let indices = palette.index_pixels(
+                &pixels,
+                bounds.width as usize,
+                origin,
+                config.dither,
+                transparent,
+            );
+            Frame::from_indexed_pixels(
+                bounds.width as u16,
+                bounds.height as u16,
+                indices,
+                (i > 0).then_some(transparent),
+            )
+        }
+        None => {
+            // Leave one slot free for the transparent index
+            let mut palette = Palette::build(
+                &pixels,
+                max_colors(config.quality),
+                config.quantizer,
+                config.quality,
+            );
+            let transparent = palette.len() as u8;
+            let indices = palette.index_pixels(
+                &pixels,
+                bounds.width as usize,
+                origin,
+                config.dither,
+                transparent,
+            );
+            Frame::from_palette_pixels(
+                bounds.width as u16,
+                bounds.height as u16,
+                indices,
+                with_transparent_entry(palette.to_rgb_bytes()),
+                (i > 0).then_some(transparent),
+            )
+        }
+    };
+    frame.left = bounds.x as u16;
+    frame.top = bounds.y as u16;
+    frame.delay = frame_delay;
+    // Keep each frame on the canvas so the next sub-rectangle draws over it
+    frame.dispose = DisposalMethod::Keep;
+    frame.make_lzw_pre_encoded();
+    Ok((frame, unchanged))
+}
+
+/// Build one palette from evenly sampled frames
+fn build_global_palette(
+    frames: &dyn FrameSource,
+    config: &ExportConfig,
+) -> Result<Palette, String> {
+    let count = frames.len().min(GLOBAL_PALETTE_SAMPLES);
+    let mut pixels = Vec::new();
+    for i in 0..count {
+        let index = i * frames.len() / count;
+        pixels.extend_from_slice(frames.frame(index)?.as_raw());
+    }
+    Ok(Palette::build(
+        &pixels,
+        max_colors(config.quality),
+        config.quantizer,
+        config.quality,
+    ))
+}
+
+/// Palette size for a quality (1-100): full palette from 50 up, fewer colors below
+/// so low quality actually shrinks the file
+fn max_colors(quality: u32) -> usize {
+    let quality = quality.clamp(1, 100) as usize;
+    if quality >= 50 {
+        MAX_COLORS
+    } else {
+        32 + (quality - 1) * (MAX_COLORS - 32) / 49
+    }
+}
+
+/// Append the (black) transparent entry after the palette colors
+fn with_transparent_entry(mut table: Vec<u8>) -> Vec<u8> {
+    table.extend_from_slice(&[0, 0, 0]);
+    table
+}
+
+/// GIF delays are in 1/100 s. Rounding the running total instead of each delay
+/// keeps the overall playback time from drifting (e.g. 30 fps = 3, 4, 3, 3, 4...).
+fn to_centiseconds(delays_ms: &[u32], count: usize) -> Vec<u16> {
+    let mut elapsed_ms = 0u64;
+    let mut elapsed_cs = 0u64;
+    (0..count)
+        .map(|i| {
+            elapsed_ms += delays_ms.get(i).copied().unwrap_or(100) as u64;
+            let end_cs = ((elapsed_ms + 5) / 10).max(elapsed_cs + 1);
+            let delay = (end_cs - elapsed_cs).min(MAX_DELAY as u64) as u16;
+            elapsed_cs = end_cs;
+            delay
+        })
+        .collect()
+}
+
+fn full_bounds(img: &RgbaImage) -> Bounds {
+    Bounds {
+        x: 0,
+        y: 0,
+        width: img.width(),
+        height: img.height(),
+    }
+}
+
+/// Bounding box of pixels that differ between two equally sized frames
+fn changed_bounds(prev: &RgbaImage, curr: &RgbaImage) -> Option<Bounds> {
+    let width = curr.width() as usize;
+    let row_bytes = width * 4;
+    let prev_raw = prev.as_raw();
+    let curr_raw = curr.as_raw();
+
+    let rows_differ = |y: usize| {
+        prev_raw[y * row_bytes..(y + 1) * row_bytes] != curr_raw[y * row_bytes..(y + 1) * row_bytes]
+    };
+
+    let height = curr.height() as usize;
+    let top = (0..height).find(|&y| rows_differ(y))?;
+    let bottom = (top..height).rev().find(|&y| rows_differ(y)).unwrap_or(top);
+
+    let mut left = width - 1;
+    let mut right = 0;
+    for y in top..=bottom {
+        let row = y * row_bytes;
+        let prev_row = &prev_raw[row..row + row_bytes];
+        let curr_row = &curr_raw[row..row + row_bytes];
+        if prev_row == curr_row {
+            continue;
+        }
+        if let Some(x) =
+            (0..left).find(|&x| prev_row[x * 4..x * 4 + 4] != curr_row[x * 4..x * 4 + 4])
+        {
+            left = x;
+        }
+        if let Some(x) = (right..width)
+            .rev()
+            .find(|&x| prev_row[x * 4..x * 4 + 4] != curr_row[x * 4..x * 4 + 4])
+        {
+            right = right.max(x);
+        }
+    }
+
+    Some(Bounds {
+        x: left as u32,
+        y: top as u32,
+        width: (right - left + 1) as u32,
+        height: (bottom - top + 1) as u32,
+    })
+}
+
+/// RGBA pixels of `curr` inside `bounds`, with pixels unchanged from `prev`
+/// made fully transparent so the previous frame shows through
+fn delta_pixels(prev: &RgbaImage, curr: &RgbaImage, bounds: Bounds) -> Vec<u8> {
+    let mut out = Vec::with_capacity((bounds.width * bounds.height * 4) as usize);
+    for y in bounds.y..bounds.y + bounds.height {
+        for x in bounds.x..bounds.x + bounds.width {
+            let p = curr.get_pixel(x, y);
+            if prev.get_pixel(x, y) == p {
+                out.extend_from_slice(&[0, 0, 0, 0]);
+            } else {
+                out.extend_from_slice(&p.0);
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,282 @@
+//! Keystroke logging during recording and the on-frame keystroke badge
+//!
+//! The keyboard is polled (no event tap or Accessibility prompt needed), so only
+//! presses that last longer than the poll interval are seen. Only shortcuts and
+//! special keys (arrows, return, esc, ...) are logged: plain typing is skipped
+//! so passwords never reach the key log on disk. Logging is macOS-only;
+//! elsewhere nothing is recorded.
+
+use image::{Rgba, RgbaImage};
+
+use crate::draw;
+use crate::types::{BadgePosition, KeyEvent, KeystrokeBadge};
+
+/// How long a key stays fully visible before it starts fading
+const HOLD_MS: u64 = 1000;
+/// Most recent keys shown side by side
+const MAX_VISIBLE: usize = 4;
+/// Badge font sizes accepted from the editor
+pub const MIN_FONT_SIZE: u32 = 8;
+pub const MAX_FONT_SIZE: u32 = 200;
+
+/// macOS virtual key codes (ANSI layout) that get logged; modifiers are folded
+/// into the label of the key they're held with
+#[cfg(target_os = "macos")]
+const KEY_NAMES: &[(u16, &str)] = &[
+    (0x00, "A"),
+    (0x01, "S"),
+    (0x02, "D"),
+    (0x03, "F"),
+    (0x04, "H"),
+    (0x05, "G"),
+    (0x06, "Z"),
+    (0x07, "X"),
+    (0x08, "C"),
+    (0x09, "V"),
+    (0x0B, "B"),
+    (0x0C, "Q"),
+    (0x0D, "W"),
+    (0x0E, "E"),
+    (0x0F, "R"),
+    (0x10, "Y"),
+    (0x11, "T"),
+    (0x12, "1"),
+    (0x13, "2"),
+    (0x14, "3"),
+    (0x15, "4"),
+    (0x16, "6"),
+    (0x17, "5"),
+    (0x18, "="),
+    (0x19, "9"),
+    (0x1A, "7"),
+    (0x1B, "-"),
+    (0x1C, "8"),
+    (0x1D, "0"),
+    (0x1E, "]"),
+    (0x1F, "O"),
+    (0x20, "U"),
+    (0x21, "["),
+    (0x22, "I"),
+    (0x23, "P"),
+    (0x24, "⏎"),
+    (0x25, "L"),
+    (0x26, "J"),
+    (0x27, "'"),
+    (0x28, "K"),
+    (0x29, ";"),
+    (0x2A, "\\"),
+    (0x2B, ","),
+    (0x2C, "/"),
+    (0x2D, "N"),
+    (0x2E, "M"),
+    (0x2F, "."),
+    (0x30, "⇥"),
+    (0x31, "␣"),
+    (0x32, "`"),
+    (0x33, "⌫"),
+    (0x35, "esc"),
+    (0x60, "F5"),
+    (0x61, "F6"),
+    (0x62, "F7"),
+    (0x63, "F3"),
+    (0x64, "F8"),
+    (0x65, "F9"),
+    (0x67, "F11"),
+    (0x6D, "F10"),
+    (0x6F, "F12"),
+    (0x73, "↖"),
+    (0x74, "⇞"),
+    (0x75, "⌦"),
+    (0x76, "F4"),
+    (0x77, "↘"),
+    (0x78, "F2"),
+    (0x79, "⇟"),
+    (0x7A, "F1"),
+    (0x7B, "←"),
+    (0x7C, "→"),
+    (0x7D, "↓"),
+    (0x7E, "↑"),
+];
+
+/// Detects new key presses between polls
+#[derive(Default)]
+pub struct KeyLogger {
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    down: u128, // bit per virtual key code
+}
+
+impl KeyLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keys pressed since the last poll, stamped with `timestamp_ms`
+    #[cfg(target_os = "macos")]
+    pub fn poll(&mut self, timestamp_ms: u64) -> Vec<KeyEvent> {
+        #[link(name = "CoreGraphics", kind = "framework")]
+        extern "C" {
+            fn CGEventSourceKeyState(state_id: i32, key: u16) -> bool;
+            fn CGEventSourceFlagsState(state_id: i32) -> u64;
+        }
+        const COMBINED_SESSION_STATE: i32 = 0;
+
+        let mut events = Vec::new();
+        let mut down = 0u128;
+        let flags = unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) };
+        for &(code, name) in KEY_NAMES {
+            if !unsafe { CGEventSourceKeyState(COMBINED_SESSION_STATE, code) } {
+                continue;
+            }
+            let bit = 1u128 << code;
+            down |= bit;
+            if self.down & bit == 0 && is_shortcut(name, flags) {
+                events.push(KeyEvent {
+                    timestamp_ms,
+                    label: format!("{}{}", modifier_symbols(flags), name),
+                });
+            }
+        }
+        self.down = down;
+        events
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn poll(&mut self, _timestamp_ms: u64) -> Vec<KeyEvent> {
+        Vec::new()
+    }
+}
+
+/// Whether a press is worth logging: anything but a printable key typed alone
+/// or with shift, which is just text
+#[cfg(target_os = "macos")]
+fn is_shortcut(name: &str, flags: u64) -> bool {
+    const COMMAND_MODIFIERS: u64 = 0x40000 | 0x80000 | 0x100000; // ⌃ ⌥ ⌘
+    let printable = name == "␣" || (name.len() == 1 && name.is_ascii());
+    !printable || flags & COMMAND_MODIFIERS != 0
+}
+
+/// In the order macOS menus show them
+#[cfg(target_os = "macos")]
+fn modifier_symbols(flags: u64) -> String {
+    const MODIFIERS: [(u64, char); 4] = [
+        (0x40000, '⌃'),  // kCGEventFlagMaskControl
+        (0x80000, '⌥'),  // kCGEventFlagMaskAlternate
+        (0x20000, '⇧'),  // kCGEventFlagMaskShift
+        (0x100000, '⌘'), // kCGEventFlagMaskCommand
+    ];
+    MODIFIERS
+        .iter()
+        .filter(|(mask, _)| flags & mask != 0)
+        .map(|(_, symbol)| symbol)
+        .collect()
+}
+
+/// Renders the badge for one export
+pub struct KeystrokeOverlay<'a> {
+    events: &'a [KeyEvent],
+    config: &'a KeystrokeBadge,
+}
+
+impl<'a> KeystrokeOverlay<'a> {
+    /// Returns None when the badge is off or nothing was logged
+    pub fn new(events: &'a [KeyEvent], config: &'a KeystrokeBadge) -> Option<Self> {
+        (config.enabled && !events.is_empty()).then_some(Self { events, config })
+    }
+
+    fn visible_ms(&self) -> u64 {
+        HOLD_MS + self.config.fade_ms as u64
+    }
+
+    /// Keys on screen at `time_ms`, oldest first
+    fn visible_at(&self, time_ms: u64) -> &[KeyEvent] {
+        // Sorted by time; `FrameStore::set_keystrokes` keeps edits sorted
+        let end = self.events.partition_point(|e| e.timestamp_ms <= time_ms);
+        let shown = &self.events[..end];
+        let first = shown
+            .iter()
+            .rposition(|e| e.timestamp_ms + self.visible_ms() <= time_ms)
+            .map_or(0, |i| i + 1);
+        let first = first.max(shown.len().saturating_sub(MAX_VISIBLE));
+        &shown[first..]
+    }
+
+    /// Whether the badge can look different anywhere in `from_ms..=to_ms`
+    pub fn changes_between(&self, from_ms: u64, to_ms: u64) -> bool {
+        !self.visible_at(from_ms).is_empty()
+            || !self.visible_at(to_ms).is_empty()
+            || self
+                .events
+                .iter()
+                .any(|e| e.timestamp_ms > from_ms && e.timestamp_ms <= to_ms)
+    }
+
+    /// Draw the badge for `time_ms` on the recording timeline
+    pub fn apply(&self, frame: &mut RgbaImage, time_ms: u64) {
+        let mut visible = self.visible_at(time_ms);
+        let Some(newest) = visible.last() else {
+            return;
+        };
+        let age = time_ms - newest.timestamp_ms;
+        let opacity = if age < HOLD_MS {
+            1.0
+        } else {
+            1.0 - (age - HOLD_MS) as f32 / self.config.fade_ms.max(1) as f32
+        };
+        if opacity <= 0.0 {
+            return;
+        }
+
+        let size = self.config.font_size.max(MIN_FONT_SIZE) as f32;
+        let pad_x = (size * 0.5).round() as u32;
+        let pad_y = (size * 0.25).round() as u32;
+        let margin = (size * 0.6).round() as i64;
+        let (frame_w, frame_h) = frame.dimensions();
+
+        // Drop the oldest keys until the badge fits the frame
+        let (text, (text_w, text_h)) = loop {
+            let text = visible
+                .iter()
+                .map(|e| e.label.as_str())
+                .collect::<Vec<_>>()
+                .join("  ");
+            let measured = draw::measure_text(&text, size);
+            if visible.len() == 1 || measured.0 + 2 * pad_x + 2 * margin as u32 <= frame_w {
+                break (text, measured);
+            }
+            visible = &visible[1..];
+        };
+
+        let badge_w = text_w + 2 * pad_x;
+        let badge_h = text_h + 2 * pad_y;
+        let (w, h) = (frame_w as i64, frame_h as i64);
+        let (bw, bh) = (badge_w as i64, badge_h as i64);
+        let x = match self.config.position {
+            BadgePosition::TopLeft | BadgePosition::BottomLeft => margin,
+            BadgePosition::TopCenter | BadgePosition::BottomCenter => (w - bw) / 2,
+            BadgePosition::TopRight | BadgePosition::BottomRight => w - bw - margin,
+        };
+        let y = match self.config.position {
+            BadgePosition::TopLeft | BadgePosition::TopCenter | BadgePosition::TopRight => margin,
+            _ => h - bh - margin,
+        };
+
+        let alpha = |a: f32| (a * opacity).round() as u8;
+        draw::fill_rounded_rect(
+            frame,
+            x,
+            y,
+            badge_w,
+            badge_h,
+            size * 0.35,
+            Rgba([20, 20, 20, alpha(190.0)]),
+        );
+        draw::draw_text(
+            frame,
+            &text,
+            (x + pad_x as i64) as f32,
+            (y + pad_y as i64) as f32,
+            size,
+            Rgba([255, 255, 255, alpha(255.0)]),
+        );
+    }
+}
@@ -10,19 +10,34 @@ mod macos_menu_tracking;
 #[cfg(target_os = "macos")]
 mod window_detect;
 
+mod animation;
+mod annotate;
 mod capture;
 mod commands;
 mod config;
+mod contact_sheet;
+mod cursor;
+mod draw;
+mod edit_list;
+mod export_jobs;
 mod fft_match;
+mod frame_sequence;
+mod frame_source;
+mod frame_store;
+mod gif_encoder;
+mod keystrokes;
 mod permission;
+mod quantize;
+mod redact;
 mod shortcuts;
 mod state;
 mod tray;
 mod types;
+mod video;
 mod windows;
 
-use commands::open_selector_internal;
-use shortcuts::{get_action_for_shortcut, is_show_main_shortcut, is_stop_recording_shortcut, register_shortcuts_from_config, unregister_stop_shortcuts};
+use commands::{open_selector_internal, stop_recording_internal, toggle_recording_pause_internal};
+use shortcuts::{get_action_for_shortcut, is_pause_recording_shortcut, is_show_main_shortcut, is_stop_recording_shortcut, is_timed_screenshot_shortcut, register_shortcuts_from_config};
 use state::{AppState, SharedState};
 use tray::{build_tray_menu, load_tray_icon};
 pub use types::*;
@@ -62,16 +77,29 @@ pub fn run() {
                         return;
                     }
 
-                    // If recording, any registered shortcut stops it
+                    // If recording, the pause shortcut toggles pause and any other
+                    // registered shortcut stops it
                     let is_recording = state_for_shortcut.lock().unwrap().recording;
                     if is_recording {
+                        if is_pause_recording_shortcut(shortcut) {
+                            let _ = toggle_recording_pause_internal(app, &state_for_shortcut);
+                            return;
+                        }
                         println!("[DEBUG][shortcut] 停止录制");
-                        state_for_shortcut.lock().unwrap().recording = false;
-                        // Unregister stop shortcuts since recording ended
-                        unregister_stop_shortcuts(app);
+                        stop_recording_internal(app, &state_for_shortcut);
                         return;
                     }
 
+                    // Any registered shortcut (e.g. ESC) cancels a timed screenshot
+                    {
+                        let mut s = state_for_shortcut.lock().unwrap();
+                        if s.capture_countdown {
+                            println!("[DEBUG][shortcut] 取消定时截图");
+                            s.capture_countdown = false;
+                            return;
+                        }
+                    }
+
                     // Check if scroll capturing - if so, stop and allow new captures
                     {
                         let mut s = state_for_shortcut.lock().unwrap();
@@ -107,6 +135,18 @@ pub fn run() {
                         return;
                     }
 
+                    // Timed screenshot: open the selector with the timer preselected
+                    if is_timed_screenshot_shortcut(shortcut) {
+                        println!("[DEBUG][shortcut] 定时截图");
+                        {
+                            let mut s = state_for_shortcut.lock().unwrap();
+                            s.pending_mode = Some(CaptureMode::Image);
+                            s.pending_timer_secs = Some(commands::DEFAULT_SCREENSHOT_DELAY_SECS);
+                        }
+                        let _ = open_selector_internal(app.clone());
+                        return;
+                    }
+
                     if let Some(mode) = get_action_for_shortcut(shortcut) {
                         println!("[DEBUG][shortcut] {:?} triggered -> {:?}", shortcut, mode);
                         state_for_shortcut.lock().unwrap().pending_mode = Some(mode);
@@ -118,6 +158,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(state)
+        .manage(export_jobs::ExportJobs::default())
         .invoke_handler(tauri::generate_handler![
             commands::get_screens,
             commands::get_mouse_position,
@@ -126,6 +167,7 @@ pub fn run() {
             commands::set_region,
             commands::get_pending_mode,
             commands::get_screen_snapshot,
+            commands::get_pending_timer,
             commands::clear_pending_mode,
             commands::get_window_at_cursor,
             commands::get_window_info_at_cursor,
@@ -137,15 +179,38 @@ pub fn run() {
             commands::pause_shortcuts,
             commands::resume_shortcuts,
             commands::set_developer_mode,
+            commands::get_recording_config,
+            commands::set_recording_config,
+            commands::get_exclusion_config,
+            commands::set_exclusion_config,
             commands::start_recording,
             commands::stop_recording,
+            commands::pause_recording,
+            commands::resume_recording,
+            commands::get_keystrokes,
+            commands::set_keystrokes,
+            commands::get_annotations,
+            commands::set_annotations,
+            commands::get_redactions,
+            commands::set_redactions,
             commands::get_recording_info,
+            commands::get_edits,
+            commands::apply_edit,
+            commands::undo_edit,
+            commands::redo_edit,
             commands::estimate_export_size,
             commands::export_gif,
+            commands::export_video,
+            commands::cancel_export,
+            commands::export_contact_sheet,
             commands::discard_recording,
+            commands::list_recoverable_sessions,
+            commands::recover_session,
+            commands::discard_session,
             commands::get_frame_thumbnail,
             commands::get_filmstrip,
             commands::save_screenshot,
+            commands::start_timed_screenshot,
             commands::open_file,
             commands::reveal_in_folder,
             // Scroll capture commands
@@ -214,6 +279,12 @@ pub fn run() {
                             windows::set_activation_policy(0);
                         }
                     }
+                    "pause_recording" => {
+                        let _ = toggle_recording_pause_internal(app, &state_for_menu);
+                    }
+                    "stop_recording" => {
+                        stop_recording_internal(app, &state_for_menu);
+                    }
                     "screenshot" => {
                         state_for_menu.lock().unwrap().pending_mode = Some(CaptureMode::Image);
                         let _ = open_selector_internal(app.clone());
@@ -247,6 +318,14 @@ pub fn run() {
             let app_handle = app.handle().clone();
             register_shortcuts_from_config(&app_handle)?;
 
+            // Drop stale recording sessions in the background, then offer to
+            // reopen one that wasn't exported before the last quit or crash
+            let recovery_handle = app_handle.clone();
+            std::thread::spawn(move || {
+                frame_store::cleanup_sessions();
+                commands::offer_session_recovery(&recovery_handle);
+            });
+
             // Sync autostart state from config on startup
             let cfg = config::load_config();
             let autostart = app.autolaunch();
@@ -0,0 +1,316 @@
+//! Palette building and dithering for GIF export
+//!
+//! Pixels are RGBA; fully transparent pixels (alpha 0) are ignored when building
+//! palettes and map to the caller's transparent index.
+
+use std::collections::{HashMap, HashSet};
+
+use color_quant::NeuQuant;
+
+use crate::types::{GifDither, GifQuantizer};
+
+/// Upper bound on pixels fed to the quantizers, to keep palette building fast
+const MAX_SAMPLE_PIXELS: usize = 500_000;
+
+/// 8x8 Bayer threshold matrix for ordered dithering
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Ordered dither amplitude (per channel, in 0-255 units)
+const ORDERED_SPREAD: f32 = 24.0;
+
+/// A quantized palette with a nearest-color cache
+#[derive(Clone)]
+pub struct Palette {
+    colors: Vec<[u8; 3]>,
+    /// All source colors fit in the palette, so dithering would only add noise
+    exact: bool,
+    cache: HashMap<u32, u8>,
+}
+
+impl Palette {
+    /// Build a palette of at most `max_colors` from RGBA pixels
+    /// `quality` (1-100) controls how many pixels are sampled
+    pub fn build(rgba: &[u8], max_colors: usize, quantizer: GifQuantizer, quality: u32) -> Self {
+        let max_colors = max_colors.clamp(2, 256);
+
+        if let Some(colors) = exact_colors(rgba, max_colors) {
+            return Self::from_colors(colors, true);
+        }
+
+        // Map quality (1-100) to sample step (30-1): higher quality = more pixels analyzed
+        let step = 30 - ((quality.clamp(1, 100) - 1) * 29 / 99) as usize;
+        let samples = sample_opaque(rgba, step);
+
+        let colors = match quantizer {
+            GifQuantizer::NeuQuant => {
+                let mut flat = Vec::with_capacity(samples.len() * 4);
+                for c in &samples {
+                    flat.extend_from_slice(&[c[0], c[1], c[2], 255]);
+                }
+                // Samples are already thinned out, so let NeuQuant look at all of them
+                NeuQuant::new(1, max_colors, &flat)
+                    .color_map_rgb()
+                    .chunks_exact(3)
+                    .map(|c| [c[0], c[1], c[2]])
+                    .collect()
+            }
+            GifQuantizer::MedianCut => median_cut(samples, max_colors),
+        };
+        Self::from_colors(colors, false)
+    }
+
+    fn from_colors(colors: Vec<[u8; 3]>, exact: bool) -> Self {
+        let colors = if colors.is_empty() {
+            vec![[0, 0, 0]]
+        } else {
+            colors
+        };
+        Self {
+            colors,
+            exact,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Flat RGB bytes, as stored in a GIF color table
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.colors.iter().flatten().copied().collect()
+    }
+
+    /// Map an RGBA region to palette indices
+    /// `origin` is the region's position on the canvas, so ordered dither patterns
+    /// stay aligned across partial frames
+    pub fn index_pixels(
+        &mut self,
+        rgba: &[u8],
+        width: usize,
+        origin: (u32, u32),
+        dither: GifDither,
+        transparent: u8,
+    ) -> Vec<u8> {
+        let dither = if self.exact { GifDither::None } else { dither };
+        match dither {
+            GifDither::None => rgba
+                .chunks_exact(4)
+                .map(|p| {
+                    if p[3] == 0 {
+                        transparent
+                    } else {
+                        self.nearest([p[0], p[1], p[2]])
+                    }
+                })
+                .collect(),
+            GifDither::Ordered => self.index_ordered(rgba, width, origin, transparent),
+            GifDither::FloydSteinberg => self.index_floyd_steinberg(rgba, width, transparent),
+        }
+    }
+
+    fn index_ordered(
+        &mut self,
+        rgba: &[u8],
+        width: usize,
+        origin: (u32, u32),
+        transparent: u8,
+    ) -> Vec<u8> {
+        let width = width.max(1);
+        rgba.chunks_exact(4)
+            .enumerate()
+            .map(|(i, p)| {
+                if p[3] == 0 {
+                    return transparent;
+                }
+                let x = (origin.0 as usize + i % width) % 8;
+                let y = (origin.1 as usize + i / width) % 8;
+                let offset = (BAYER_8X8[y][x] as f32 / 64.0 - 0.5) * ORDERED_SPREAD;
+                let shift = |v: u8| (v as f32 + offset).round().clamp(0.0, 255.0) as u8;
+                self.nearest([shift(p[0]), shift(p[1]), shift(p[2])])
+            })
+            .collect()
+    }
+
+    fn index_floyd_steinberg(&mut self, rgba: &[u8], width: usize, transparent: u8) -> Vec<u8> {
+        let width = width.max(1);
+        let height = rgba.len() / 4 / width;
+        let mut out = Vec::with_capacity(width * height);
+
+        // Accumulated error for the current and next row, 3 channels per pixel
+        let mut curr_err = vec![0.0f32; (width + 2) * 3];
+        let mut next_err = vec![0.0f32; (width + 2) * 3];
+
+        for y in 0..height {
+            for x in 0..width {
+                let p = &rgba[(y * width + x) * 4..(y * width + x) * 4 + 4];
+                if p[3] == 0 {
+                    // Don't diffuse error into or out of transparent (unchanged) areas
+                    out.push(transparent);
+                    continue;
+                }
+
+                let e = (x + 1) * 3;
+                let wanted = [
+                    (p[0] as f32 + curr_err[e]).clamp(0.0, 255.0),
+                    (p[1] as f32 + curr_err[e + 1]).clamp(0.0, 255.0),
+                    (p[2] as f32 + curr_err[e + 2]).clamp(0.0, 255.0),
+                ];
+                let index = self.nearest([
+                    wanted[0].round() as u8,
+                    wanted[1].round() as u8,
+                    wanted[2].round() as u8,
+                ]);
+                out.push(index);
+
+                let chosen = self.colors[index as usize];
+                for c in 0..3 {
+                    let err = wanted[c] - chosen[c] as f32;
+                    curr_err[e + 3 + c] += err * 7.0 / 16.0;
+                    next_err[e - 3 + c] += err * 3.0 / 16.0;
+                    next_err[e + c] += err * 5.0 / 16.0;
+                    next_err[e + 3 + c] += err / 16.0;
+                }
+            }
+            std::mem::swap(&mut curr_err, &mut next_err);
+            next_err.iter_mut().for_each(|v| *v = 0.0);
+        }
+
+        out
+    }
+
+    /// Index of the closest palette color (squared RGB distance), cached per color
+    fn nearest(&mut self, color: [u8; 3]) -> u8 {
+        let key = u32::from_le_bytes([color[0], color[1], color[2], 0]);
+        if let Some(&index) = self.cache.get(&key) {
+            return index;
+        }
+
+        let mut best = 0;
+        let mut best_dist = u32::MAX;
+        for (i, c) in self.colors.iter().enumerate() {
+            let dist = (0..3)
+                .map(|k| {
+                    let d = c[k] as i32 - color[k] as i32;
+                    (d * d) as u32
+                })
+                .sum::<u32>();
+            if dist < best_dist {
+                best = i;
+                best_dist = dist;
+                if dist == 0 {
+                    break;
+                }
+            }
+        }
+
+        self.cache.insert(key, best as u8);
+        best as u8
+    }
+}
+
+/// The distinct opaque colors, if there are no more than `max_colors` of them
+fn exact_colors(rgba: &[u8], max_colors: usize) -> Option<Vec<[u8; 3]>> {
+    let mut seen = HashSet::new();
+    let mut colors = Vec::new();
+    for p in rgba.chunks_exact(4) {
+        if p[3] == 0 {
+            continue;
+        }
+        let color = [p[0], p[1], p[2]];
+        if seen.insert(color) {
+            if colors.len() == max_colors {
+                return None;
+            }
+            colors.push(color);
+        }
+    }
+    Some(colors)
+}
+
+/// Every `step`-th opaque pixel, thinned further to stay under `MAX_SAMPLE_PIXELS`
+fn sample_opaque(rgba: &[u8], step: usize) -> Vec<[u8; 3]> {
+    let pixel_count = rgba.len() / 4;
+    let step = step.max(pixel_count / MAX_SAMPLE_PIXELS).max(1);
+    rgba.chunks_exact(4)
+        .step_by(step)
+        .filter(|p| p[3] != 0)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect()
+}
+
+/// Classic median cut: repeatedly split the box with the widest channel range
+/// at its median, then average each box
+fn median_cut(samples: Vec<[u8; 3]>, max_colors: usize) -> Vec<[u8; 3]> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![samples];
+    while boxes.len() < max_colors {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (channel, range) = widest_channel(b);
+                (i, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range);
+
+        let Some((index, channel, range)) = widest else {
+            break;
+        };
+        if range == 0 {
+            break;
+        }
+
+        let mut lower = boxes.swap_remove(index);
+        lower.sort_unstable_by_key(|c| c[channel]);
+        let upper = lower.split_off(lower.len() / 2);
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|b| {
+            let mut sum = [0u64; 3];
+            for c in b {
+                for k in 0..3 {
+                    sum[k] += c[k] as u64;
+                }
+            }
+            let n = b.len() as u64;
+            [
+                ((sum[0] + n / 2) / n) as u8,
+                ((sum[1] + n / 2) / n) as u8,
+                ((sum[2] + n / 2) / n) as u8,
+            ]
+        })
+        .collect()
+}
+
+fn widest_channel(colors: &[[u8; 3]]) -> (usize, u8) {
+    let mut min = [u8::MAX; 3];
+    let mut max = [0u8; 3];
+    for c in colors {
+        for k in 0..3 {
+            min[k] = min[k].min(c[k]);
+            max[k] = max[k].max(c[k]);
+        }
+    }
+    (0..3)
+        .map(|k| (k, max[k] - min[k]))
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
@@ -0,0 +1,104 @@
+//! Redaction of sensitive areas (tokens, emails, customer data)
+//!
+//! Redactions overwrite pixels in place before an image is encoded, copied or
+//! previewed; they're never a layer on top of the original. Pixelate and blur
+//! average over blocks large enough that text can't be read back, and blur
+//! starts from a pixelation so it can't be deconvolved into the original.
+
+use image::{Rgba, RgbaImage};
+
+use crate::types::{Redaction, RedactionMode, TimedRedaction};
+
+/// Smallest pixelation block, in redaction units (scaled with the image)
+const MIN_BLOCK: f32 = 10.0;
+/// Blocks span at least this fraction of the area's shorter side
+const BLOCK_FRACTION: u32 = 6;
+const SOLID_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Redact a screenshot or scroll capture. Coordinates are multiplied by `scale`,
+/// e.g. from selection (logical) to captured pixels.
+pub fn apply_all(img: &mut RgbaImage, redactions: &[Redaction], scale: f32) {
+    for redaction in redactions {
+        apply(img, redaction, scale);
+    }
+}
+
+/// Redact source frame `index` of a recording
+pub fn apply_for_frame(frame: &mut RgbaImage, redactions: &[TimedRedaction], index: usize) {
+    for timed in redactions
+        .iter()
+        .filter(|r| (r.start_frame..r.end_frame).contains(&index))
+    {
+        apply(frame, &timed.redaction, 1.0);
+    }
+}
+
+fn apply(img: &mut RgbaImage, redaction: &Redaction, scale: f32) {
+    // Cover every pixel the area touches; negative sizes are drags up or left
+    let (ax, bx) = (redaction.x * scale, (redaction.x + redaction.width) * scale);
+    let (ay, by) = (
+        redaction.y * scale,
+        (redaction.y + redaction.height) * scale,
+    );
+    let x0 = (ax.min(bx).floor().max(0.0) as u32).min(img.width());
+    let y0 = (ay.min(by).floor().max(0.0) as u32).min(img.height());
+    let x1 = (ax.max(bx).ceil().max(0.0) as u32).min(img.width());
+    let y1 = (ay.max(by).ceil().max(0.0) as u32).min(img.height());
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+    let (width, height) = (x1 - x0, y1 - y0);
+    let block = ((MIN_BLOCK * scale).round() as u32).max(width.min(height) / BLOCK_FRACTION);
+
+    match redaction.mode {
+        RedactionMode::Solid => {
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    img.put_pixel(x, y, SOLID_COLOR);
+                }
+            }
+        }
+        RedactionMode::Pixelate => pixelate(img, (x0, y0), (x1, y1), block),
+        RedactionMode::Blur => {
+            pixelate(img, (x0, y0), (x1, y1), block);
+            // Blur only what's inside, so the edges don't pull in the surroundings
+            let area = image::imageops::crop_imm(img, x0, y0, width, height).to_image();
+            let blurred = image::imageops::blur(&area, block as f32 / 2.0);
+            for (x, y, pixel) in blurred.enumerate_pixels() {
+                let Rgba([r, g, b, _]) = *pixel;
+                img.put_pixel(x0 + x, y0 + y, Rgba([r, g, b, 255]));
+            }
+        }
+    }
+}
+
+/// Replace each `block`-sized cell of the area with its average color
+fn pixelate(img: &mut RgbaImage, (x0, y0): (u32, u32), (x1, y1): (u32, u32), block: u32) {
+    let block = block.max(1);
+    for cell_y in (y0..y1).step_by(block as usize) {
+        for cell_x in (x0..x1).step_by(block as usize) {
+            let (cell_x1, cell_y1) = ((cell_x + block).min(x1), (cell_y + block).min(y1));
+            let mut sum = [0u64; 3];
+            for y in cell_y..cell_y1 {
+                for x in cell_x..cell_x1 {
+                    let pixel = img.get_pixel(x, y);
+                    for (total, &value) in sum.iter_mut().zip(&pixel.0[..3]) {
+                        *total += value as u64;
+                    }
+                }
+            }
+            let count = ((cell_x1 - cell_x) * (cell_y1 - cell_y)) as u64;
+            let average = Rgba([
+                (sum[0] / count) as u8,
+                (sum[1] / count) as u8,
+                (sum[2] / count) as u8,
+                255,
+            ]);
+            for y in cell_y..cell_y1 {
+                for x in cell_x..cell_x1 {
+                    img.put_pixel(x, y, average);
+                }
+            }
+        }
+    }
+}
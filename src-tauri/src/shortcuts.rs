@@ -5,6 +5,10 @@ use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
 use crate::config;
 use crate::types::CaptureMode;
 
+/// Actions whose shortcuts are only registered while recording, so they don't
+/// hijack keys like ESC globally
+const RECORDING_ACTIONS: [&str; 2] = ["stop_recording", "pause_recording"];
+
 /// Parse shortcut string to Shortcut struct (e.g., "Alt+A" -> Shortcut)
 pub fn parse_shortcut(s: &str) -> Result<Shortcut, String> {
     let parts: Vec<&str> = s.split('+').collect();
@@ -78,8 +82,8 @@ pub fn get_action_for_shortcut(shortcut: &Shortcut) -> Option<CaptureMode> {
     let config = config::load_config();
 
     for (action, shortcuts) in &config.shortcuts {
-        // Skip stop_recording - it's handled locally by overlay, not as a capture mode
-        if action == "stop_recording" {
+        // Skip recording-only actions - they are not capture modes
+        if RECORDING_ACTIONS.contains(&action.as_str()) {
             continue;
         }
 
@@ -120,6 +124,16 @@ pub fn is_stop_recording_shortcut(shortcut: &Shortcut) -> bool {
     is_shortcut_for_action(shortcut, "stop_recording")
 }
 
+/// Check if a shortcut is a pause_recording shortcut
+pub fn is_pause_recording_shortcut(shortcut: &Shortcut) -> bool {
+    is_shortcut_for_action(shortcut, "pause_recording")
+}
+
+/// Check if a shortcut is a timed_screenshot shortcut
+pub fn is_timed_screenshot_shortcut(shortcut: &Shortcut) -> bool {
+    is_shortcut_for_action(shortcut, "timed_screenshot")
+}
+
 /// Check if a shortcut is a show_main shortcut
 pub fn is_show_main_shortcut(shortcut: &Shortcut) -> bool {
     is_shortcut_for_action(shortcut, "show_main")
@@ -145,8 +159,8 @@ fn is_shortcut_for_action(shortcut: &Shortcut, action: &str) -> bool {
 }
 
 /// Register shortcuts from config (called at startup and when config changes)
-/// NOTE: stop_recording shortcuts are NOT registered here - they are dynamically
-/// registered/unregistered when recording starts/stops to avoid hijacking ESC globally
+/// NOTE: stop_recording / pause_recording shortcuts are NOT registered here - they are
+/// dynamically registered/unregistered when recording starts/stops to avoid hijacking ESC globally
 pub fn register_shortcuts_from_config(app: &AppHandle) -> Result<(), String> {
     let config = config::load_config();
 
@@ -155,8 +169,8 @@ pub fn register_shortcuts_from_config(app: &AppHandle) -> Result<(), String> {
     }
 
     for (action, shortcuts) in &config.shortcuts {
-        // Skip stop_recording - it's dynamically registered only during recording
-        if action == "stop_recording" {
+        // Skip recording-only actions - they are dynamically registered during recording
+        if RECORDING_ACTIONS.contains(&action.as_str()) {
             continue;
         }
 
@@ -187,10 +201,13 @@ pub fn register_shortcuts_from_config(app: &AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Register stop_recording shortcuts (call when recording starts)
-pub fn register_stop_shortcuts(app: &AppHandle) {
+/// Register stop_recording / pause_recording shortcuts (call when recording starts)
+pub fn register_recording_shortcuts(app: &AppHandle) {
     let config = config::load_config();
-    if let Some(shortcuts) = config.shortcuts.get("stop_recording") {
+    for action in RECORDING_ACTIONS {
+        let Some(shortcuts) = config.shortcuts.get(action) else {
+            continue;
+        };
         for cfg in shortcuts {
             if !cfg.enabled {
                 continue;
@@ -198,19 +215,22 @@ pub fn register_stop_shortcuts(app: &AppHandle) {
             let shortcut_str = cfg.to_shortcut_string();
             if let Ok(shortcut) = parse_shortcut(&shortcut_str) {
                 if let Err(e) = app.global_shortcut().register(shortcut) {
-                    eprintln!("[shortcuts] Failed to register stop shortcut ({}): {}", shortcut_str, e);
+                    eprintln!("[shortcuts] Failed to register {} shortcut ({}): {}", action, shortcut_str, e);
                 } else {
-                    println!("[shortcuts] Registered stop_recording -> {}", shortcut_str);
+                    println!("[shortcuts] Registered {} -> {}", action, shortcut_str);
                 }
             }
         }
     }
 }
 
-/// Unregister stop_recording shortcuts (call when recording stops)
-pub fn unregister_stop_shortcuts(app: &AppHandle) {
+/// Unregister stop_recording / pause_recording shortcuts (call when recording stops)
+pub fn unregister_recording_shortcuts(app: &AppHandle) {
     let config = config::load_config();
-    if let Some(shortcuts) = config.shortcuts.get("stop_recording") {
+    for action in RECORDING_ACTIONS {
+        let Some(shortcuts) = config.shortcuts.get(action) else {
+            continue;
+        };
         for cfg in shortcuts {
             if !cfg.enabled {
                 continue;
@@ -218,9 +238,9 @@ pub fn unregister_stop_shortcuts(app: &AppHandle) {
             let shortcut_str = cfg.to_shortcut_string();
             if let Ok(shortcut) = parse_shortcut(&shortcut_str) {
                 if let Err(e) = app.global_shortcut().unregister(shortcut) {
-                    eprintln!("[shortcuts] Failed to unregister stop shortcut ({}): {}", shortcut_str, e);
+                    eprintln!("[shortcuts] Failed to unregister {} shortcut ({}): {}", action, shortcut_str, e);
                 } else {
-                    println!("[shortcuts] Unregistered stop_recording -> {}", shortcut_str);
+                    println!("[shortcuts] Unregistered {} -> {}", action, shortcut_str);
                 }
             }
         }
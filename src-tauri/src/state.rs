@@ -1,16 +1,52 @@
-use crate::types::{CaptureMode, Region};
+use crate::edit_list::EditList;
+use crate::frame_store::FrameStore;
+use crate::types::{CaptureMode, Region, StopReason, TimelapseOptions};
 use image::RgbaImage;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+/// One recording, edited in its own editor window
+pub struct RecordingSession {
+    pub frames: FrameStore,
+    pub mode: CaptureMode, // Gif or Video, decides which editor/export is used
+    pub fps: u32,
+    pub timelapse: Option<TimelapseOptions>, // None = regular recording at fps
+    pub stop_reason: StopReason,             // set before `recording` is cleared
+    pub edits: EditList,
+}
+
+impl RecordingSession {
+    pub fn new(
+        frames: FrameStore,
+        mode: CaptureMode,
+        fps: u32,
+        timelapse: Option<TimelapseOptions>,
+    ) -> Self {
+        // Timelapses start with each capture shown for about one 10fps output frame
+        let speed = timelapse.map_or(1.0, |t| t.interval_ms as f32 / 100.0);
+        Self {
+            frames,
+            mode,
+            fps,
+            timelapse,
+            stop_reason: StopReason::User,
+            edits: EditList::new(speed),
+        }
+    }
+}
+
 pub struct AppState {
     pub recording: bool,
+    pub recording_paused: bool, // no frames are captured and the timeline doesn't advance
+    pub recording_session: Option<String>, // session being recorded into
+    pub sessions: HashMap<String, RecordingSession>, // keyed by session ID
     pub region: Option<Region>,
-    pub frames: Vec<RgbaImage>,
-    pub recording_fps: u32,
     pub screen_x: i32,
     pub screen_y: i32,
     pub screen_scale: f32,
     pub pending_mode: Option<CaptureMode>,
+    pub pending_timer_secs: Option<u32>, // screenshot delay preset for the selector
+    pub capture_countdown: bool,         // a timed screenshot is counting down
     pub screen_snapshot: Option<String>,
     pub shortcuts_paused_for_editing: bool,
     pub shortcuts_paused_for_tray_menu: bool,
@@ -25,13 +61,16 @@ impl Default for AppState {
     fn default() -> Self {
         Self {
             recording: false,
+            recording_paused: false,
+            recording_session: None,
+            sessions: HashMap::new(),
             region: None,
-            frames: Vec::new(),
-            recording_fps: 30,
             screen_x: 0,
             screen_y: 0,
             screen_scale: 1.0,
             pending_mode: None,
+            pending_timer_secs: None,
+            capture_countdown: false,
             screen_snapshot: None,
             shortcuts_paused_for_editing: false,
             shortcuts_paused_for_tray_menu: false,
@@ -43,4 +82,23 @@ impl Default for AppState {
     }
 }
 
+impl AppState {
+    pub fn session(&self, id: &str) -> Result<&RecordingSession, String> {
+        self.sessions
+            .get(id)
+            .ok_or_else(|| "Recording session not found".to_string())
+    }
+
+    pub fn session_mut(&mut self, id: &str) -> Result<&mut RecordingSession, String> {
+        self.sessions
+            .get_mut(id)
+            .ok_or_else(|| "Recording session not found".to_string())
+    }
+
+    /// Whether any editor has a recording open
+    pub fn has_frames(&self) -> bool {
+        self.sessions.values().any(|s| !s.frames.is_empty())
+    }
+}
+
 pub type SharedState = Arc<Mutex<AppState>>;
@@ -4,6 +4,7 @@ use tauri::image::Image as TauriImage;
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
 
+use crate::state::SharedState;
 use crate::types::Region;
 
 /// Build tray menu with current shortcuts from config
@@ -41,6 +42,21 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
         .map(|s| s.to_shortcut_string())
         .unwrap_or_else(|| "Alt+O".to_string());
 
+    let pause_shortcut = cfg
+        .shortcuts
+        .get("pause_recording")
+        .and_then(|v| v.first())
+        .map(|s| s.to_shortcut_string())
+        .unwrap_or_else(|| "Alt+P".to_string());
+
+    let (recording, paused) = app
+        .try_state::<SharedState>()
+        .map(|state| {
+            let s = state.lock().unwrap();
+            (s.recording, s.recording_paused)
+        })
+        .unwrap_or_default();
+
     let menu_show = MenuItem::with_id(app, "show", "Show Lovshot", true, Some(show_main_shortcut.as_str()))?;
     let menu_sep0 = PredefinedMenuItem::separator(app)?;
     let menu_screenshot = MenuItem::with_id(
@@ -63,7 +79,7 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
         app,
         "video",
         "Record Video",
-        false,
+        true,
         Some(video_shortcut.as_str()),
     )?;
     let menu_sep1 = PredefinedMenuItem::separator(app)?;
@@ -73,7 +89,7 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
     let menu_sep3 = PredefinedMenuItem::separator(app)?;
     let menu_quit = MenuItem::with_id(app, "quit", "Quit Lovshot", true, None::<&str>)?;
 
-    Menu::with_items(
+    let menu = Menu::with_items(
         app,
         &[
             &menu_show,
@@ -89,7 +105,24 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error
             &menu_sep3,
             &menu_quit,
         ],
-    )
+    )?;
+
+    // Recording controls go right below "Show Lovshot" while recording
+    if recording {
+        let menu_pause = MenuItem::with_id(
+            app,
+            "pause_recording",
+            if paused { "Resume Recording" } else { "Pause Recording" },
+            true,
+            Some(pause_shortcut.as_str()),
+        )?;
+        let menu_stop =
+            MenuItem::with_id(app, "stop_recording", "Stop Recording", true, None::<&str>)?;
+        let menu_sep_recording = PredefinedMenuItem::separator(app)?;
+        menu.insert_items(&[&menu_pause, &menu_stop, &menu_sep_recording], 2)?;
+    }
+
+    Ok(menu)
 }
 
 /// Update tray menu with current config (call after shortcut changes)
@@ -115,23 +148,47 @@ pub fn load_tray_icon(is_recording: bool) -> Option<TauriImage<'static>> {
     Some(TauriImage::new_owned(rgba.into_raw(), width, height))
 }
 
-/// Update tray icon (recording state)
-pub fn update_tray_icon(app: &AppHandle, is_recording: bool) {
-    if let Some(icon) = load_tray_icon(is_recording) {
+/// Recording icon at reduced opacity, shown while a recording is paused
+fn load_paused_tray_icon() -> Option<TauriImage<'static>> {
+    let img = image::load_from_memory(include_bytes!("../icons/tray-recording.png")).ok()?;
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        pixel[3] = (pixel[3] as u16 * 2 / 5) as u8;
+    }
+    let (width, height) = rgba.dimensions();
+    Some(TauriImage::new_owned(rgba.into_raw(), width, height))
+}
+
+/// Update tray icon and menu (recording / paused state)
+pub fn update_tray_icon(app: &AppHandle, is_recording: bool, is_paused: bool) {
+    let icon = if is_recording && is_paused {
+        load_paused_tray_icon()
+    } else {
+        load_tray_icon(is_recording)
+    };
+    if let Some(icon) = icon {
         if let Some(tray) = app.tray_by_id("main") {
             let _ = tray.set_icon(Some(icon));
-            let tooltip = if is_recording {
-                "Lovshot - Recording... (Option+A to stop)"
-            } else {
-                "Lovshot - Option+A to capture"
+            let tooltip = match (is_recording, is_paused) {
+                (true, true) => "Lovshot - Recording paused (Option+P to resume)",
+                (true, false) => "Lovshot - Recording... (Option+A to stop)",
+                _ => "Lovshot - Option+A to capture",
             };
             let _ = tray.set_tooltip(Some(tooltip));
         }
     }
+    // Pause/Resume and Stop entries follow the recording state
+    update_tray_menu(app);
 }
 
 /// Create recording border overlay window
-pub fn create_recording_overlay(app: &AppHandle, region: &Region, static_mode: bool) {
+/// `countdown_secs` shows a countdown above the region (timed screenshots)
+pub fn create_recording_overlay(
+    app: &AppHandle,
+    region: &Region,
+    static_mode: bool,
+    countdown_secs: Option<u32>,
+) {
     if app.get_webview_window("recording-overlay").is_some() {
         return;
     }
@@ -155,6 +212,9 @@ pub fn create_recording_overlay(app: &AppHandle, region: &Region, static_mode: b
     if static_mode {
         url.push_str("&static=1");
     }
+    if let Some(secs) = countdown_secs {
+        url.push_str(&format!("&countdown={}", secs));
+    }
 
     let win = WebviewWindowBuilder::new(app, "recording-overlay", WebviewUrl::App(url.into()))
         .title("Recording Overlay")
@@ -11,6 +11,7 @@ pub struct Region {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RecordingState {
     pub is_recording: bool,
+    pub is_paused: bool,
     pub frame_count: u32,
 }
 
@@ -19,6 +20,21 @@ pub struct SaveResult {
     pub success: bool,
     pub path: Option<String>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub export_params: Option<ExportParams>,
+    #[serde(default)]
+    pub job_id: Option<u64>, // export job that produced this result
+}
+
+/// Parameters an animated export actually used (may differ from the request
+/// when `max_bytes` forced a smaller output)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExportParams {
+    pub quality: u32,
+    pub output_scale: f32,
+    pub target_fps: u32,
+    pub bytes: u64,
+    pub attempts: u32,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -30,27 +46,380 @@ pub struct ExportConfig {
     pub loop_mode: String, // "infinite", "once", "pingpong"
     #[serde(default = "default_quality")]
     pub quality: u32, // encoding quality (1-100, higher = better quality but slower)
-    #[serde(default = "default_speed")]
-    pub speed: f32, // playback speed (affects duration, not frame count)
     pub output_path: Option<String>, // custom output path from Finder dialog
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub palette: GifPalette,
+    #[serde(default)]
+    pub quantizer: GifQuantizer,
+    #[serde(default)]
+    pub dither: GifDither,
+    #[serde(default)]
+    pub max_bytes: Option<u64>, // fit-to-size: lower quality/scale/fps until the file fits
+    #[serde(default)]
+    pub max_in_flight_frames: Option<usize>, // GIF: frames encoded ahead of the writer (default 2 × cores)
+    #[serde(default = "default_true")]
+    pub show_cursor: bool, // draw the pointer sampled during recording
+    #[serde(default)]
+    pub highlight_clicks: bool, // expanding ring on each left click
+    #[serde(default)]
+    pub keystrokes: KeystrokeBadge,
+    #[serde(default)]
+    pub crop: Option<CropRect>, // applied before scaling; None = whole frame
 }
 
-fn default_quality() -> u32 {
-    80
+/// Part of the recorded frames to export, in recorded frame pixels
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRect {
+    /// The part of the rect inside a `width`×`height` frame; None if that's
+    /// empty or the whole frame (nothing to crop)
+    pub fn within(&self, width: u32, height: u32) -> Option<CropRect> {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        let crop = CropRect {
+            x,
+            y,
+            width: self.width.min(width - x),
+            height: self.height.min(height - y),
+        };
+        let whole = crop.width == width && crop.height == height;
+        (crop.width > 0 && crop.height > 0 && !whole).then_some(crop)
+    }
+}
+
+/// On-frame badge showing keys logged during recording
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeystrokeBadge {
+    pub enabled: bool,
+    pub position: BadgePosition,
+    pub font_size: u32, // in recorded frame pixels, before output scaling
+    pub fade_ms: u32,   // fade-out after a key has been shown for a moment
+}
+
+impl Default for KeystrokeBadge {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            position: BadgePosition::BottomCenter,
+            font_size: 32,
+            fade_ms: 500,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgePosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomCenter,
+    BottomRight,
+}
+
+/// A key press logged while recording, e.g. "⌘⇧4"
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyEvent {
+    pub timestamp_ms: u64, // on the recording timeline, like frame timestamps
+    pub label: String,
+}
+
+/// Caption or callout shown while source frames `start_frame..end_frame` are on
+/// screen. Source frames keep annotations on the same content through edits.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimedAnnotation {
+    pub start_frame: usize,
+    pub end_frame: usize,
+    #[serde(flatten)]
+    pub annotation: Annotation,
+}
+
+/// Shape drawn onto a frame or screenshot, serialized with a `kind` tag. Positions
+/// and sizes are in frame pixels (screenshots: selection pixels), colors RGBA.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Annotation {
+    /// Text with its top-left corner at (x, y); `\n` starts a new line
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        font_size: f32,
+        color: [u8; 4],
+        #[serde(default)]
+        background: Option<[u8; 4]>, // rounded box behind the text
+    },
+    /// Highlight box
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: [u8; 4],
+        stroke_width: f32,
+        #[serde(default)]
+        fill: Option<[u8; 4]>,
+    },
+    /// Arrow pointing at (to_x, to_y)
+    Arrow {
+        from_x: f32,
+        from_y: f32,
+        to_x: f32,
+        to_y: f32,
+        color: [u8; 4],
+        stroke_width: f32,
+    },
+    /// Ellipse inscribed in the box
+    Ellipse {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: [u8; 4],
+        stroke_width: f32,
+        #[serde(default)]
+        fill: Option<[u8; 4]>,
+    },
+    /// Pen stroke through `points` ([x, y] pairs)
+    Freehand {
+        points: Vec<[f32; 2]>,
+        color: [u8; 4],
+        stroke_width: f32,
+    },
+    /// Numbered circle centered on (x, y). Steps are numbered 1, 2, 3... in list order.
+    Step {
+        x: f32,
+        y: f32,
+        radius: f32,
+        color: [u8; 4],
+    },
+}
+
+/// How a redaction scrubs the pixels under it
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionMode {
+    #[default]
+    Pixelate,
+    Blur,
+    Solid, // opaque black box
+}
+
+/// Area whose pixels are destroyed before an image is saved, copied or exported.
+/// Units are frame pixels (screenshots: selection pixels; scroll captures: stitched
+/// image pixels).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Redaction {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub mode: RedactionMode,
 }
 
-fn default_speed() -> f32 {
-    1.0
+/// Redaction applied while source frames `start_frame..end_frame` are on screen
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TimedRedaction {
+    pub start_frame: usize,
+    pub end_frame: usize,
+    #[serde(flatten)]
+    pub redaction: Redaction,
+}
+
+/// Output format produced by `export_gif`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Gif,
+    Apng,
+    Webp,
+    /// Numbered PNG files plus a manifest in a new folder
+    PngSequence,
+    /// Same as `PngSequence`, packed into one ZIP archive
+    Zip,
+}
+
+/// GIF palette strategy
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GifPalette {
+    /// Separate palette per frame (best colors per frame, may flicker)
+    #[default]
+    PerFrame,
+    /// One palette shared by all frames, built from sampled frames
+    Global,
+}
+
+/// Color quantization algorithm used to build GIF palettes
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GifQuantizer {
+    #[default]
+    #[serde(rename = "neuquant")]
+    NeuQuant,
+    MedianCut,
+}
+
+/// Dithering applied when mapping pixels to the GIF palette
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GifDither {
+    #[default]
+    None,
+    Ordered,
+    FloydSteinberg,
+}
+
+/// Contact sheet export: sampled frames laid out in a grid
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ContactSheetConfig {
+    pub start_frame: usize,
+    pub end_frame: usize,
+    pub frame_count: usize, // frames sampled evenly across the range
+    pub columns: u32,
+    #[serde(default = "default_sheet_padding")]
+    pub padding: u32, // px between cells and around the edge
+    #[serde(default)]
+    pub label: SheetLabel,
+    #[serde(default = "default_sheet_max_width")]
+    pub max_width: u32, // cells are scaled down to keep the sheet this wide at most
+    pub output_path: Option<String>,
+}
+
+/// Caption drawn below each contact sheet cell
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SheetLabel {
+    None,
+    /// `#12` (1-based recording frame)
+    FrameNumber,
+    /// `0:01.25` from the start of the recording
+    #[default]
+    Timestamp,
+}
+
+fn default_sheet_padding() -> u32 {
+    8
+}
+
+fn default_sheet_max_width() -> u32 {
+    2048
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_quality() -> u32 {
+    80
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RecordingInfo {
+    pub session_id: String,
     pub frame_count: usize,
     pub width: u32,
     pub height: u32,
     pub fps: u32,
     pub duration_ms: u64,
     pub has_frames: bool,
+    pub mode: CaptureMode,
+    pub stop_reason: StopReason,
+    pub timelapse_interval_ms: Option<u64>, // set for timelapse recordings
+}
+
+/// Timelapse capture: one frame per interval instead of the recording fps
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TimelapseOptions {
+    pub interval_ms: u64,
+    #[serde(default = "default_true")]
+    pub skip_identical: bool, // don't store frames that equal the previous capture
+}
+
+impl TimelapseOptions {
+    pub const MIN_INTERVAL_MS: u64 = 200;
+    pub const MAX_INTERVAL_MS: u64 = 600_000;
+}
+
+/// Why the last recording ended, sent with "recording-stopped"
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    #[default]
+    User,
+    MaxDuration,
+    MaxDisk,
+}
+
+/// A run of source frames in the edited timeline
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Clip {
+    pub start: usize, // source frames start..end
+    pub end: usize,
+    pub reversed: bool,
+    pub speed: f32,           // playback speed (affects duration, not frame count)
+    pub hold_ms: Option<u64>, // a still of one frame shown this long, regardless of speed
+}
+
+/// One edit to a recording's timeline. Frame ranges are `start..end` in the
+/// edited timeline, as the editor shows it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum EditOp {
+    Delete {
+        start: usize,
+        end: usize,
+    },
+    Duplicate {
+        start: usize,
+        end: usize,
+    }, // the copy goes right after the range
+    Reverse {
+        start: usize,
+        end: usize,
+    },
+    Speed {
+        start: usize,
+        end: usize,
+        speed: f32,
+    },
+    Hold {
+        frame: usize,
+        duration_ms: u64,
+    }, // a still of `frame` right after it
+}
+
+/// Edited timeline of a recording, sent to the editor after every edit
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EditState {
+    pub clips: Vec<Clip>,
+    pub frame_count: usize, // frames in the edited timeline
+    pub duration_ms: u64,   // playback duration at each clip's speed
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// Unexported recording from a previous run, found at launch
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecoverableSession {
+    pub id: String,
+    pub created_ms: u64, // unix time
+    pub mode: CaptureMode,
+    pub frame_count: usize,
+    pub duration_ms: u64,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -58,12 +427,14 @@ pub struct SizeEstimate {
     pub frame_count: usize,
     pub output_width: u32,
     pub output_height: u32,
+    pub duration_ms: u64, // playback duration after trimming and speed
     pub estimated_bytes: u64,
     pub formatted: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ExportProgress {
+    pub job_id: u64,
     pub current: usize,
     pub total: usize,
     pub stage: String,
@@ -92,6 +463,7 @@ pub enum CaptureMode {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ScrollCaptureProgress {
     pub frame_count: usize,
+    pub total_width: u32,
     pub total_height: u32,
     pub preview_base64: String,
 }
@@ -0,0 +1,370 @@
+//! Pure-Rust video encoding for recordings
+//!
+//! Encodes RGBA frames to AV1 with rav1e and muxes the packets into a WebM file
+//! as they come out of the encoder, one cluster at a time.
+//! No system libraries (ffmpeg, libvpx, ...) are required.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use image::RgbaImage;
+use rav1e::prelude::*;
+
+use crate::frame_source::FrameSource;
+
+/// Smallest frame width/height rav1e accepts
+const MIN_DIMENSION: u32 = 16;
+
+/// A single encoded AV1 temporal unit
+struct Packet {
+    data: Vec<u8>,
+    timestamp_ms: u64,
+    keyframe: bool,
+}
+
+/// Encode frames as AV1 and write them to a WebM container, loading one frame
+/// at a time. `delays_ms` gives each frame's display time; `fps` is the nominal rate the
+/// encoder is tuned for. `on_progress` is called with (encoded_count, total) after each received packet;
+/// returning an error from it aborts encoding
+pub fn encode_webm<F>(
+    frames: &dyn FrameSource,
+    delays_ms: &[u32],
+    fps: u32,
+    quality: u32,
+    path: &Path,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    if frames.is_empty() {
+        return Err("No frames to encode".to_string());
+    }
+
+    let fps = fps.max(1);
+    let total = frames.len();
+
+    // Block timecodes: each frame starts when the previous one's delay ends
+    let mut timestamps_ms = Vec::with_capacity(total);
+    let mut duration_ms = 0u64;
+    for i in 0..total {
+        timestamps_ms.push(duration_ms);
+        duration_ms += delays_ms.get(i).copied().unwrap_or(1000 / fps) as u64;
+    }
+
+    // 4:2:0 chroma subsampling requires even dimensions, so odd sizes get one
+    // more column/row; smaller sources are padded up to the encoder's minimum
+    let (src_w, src_h) = frames.dimensions();
+    let width = src_w.next_multiple_of(2).max(MIN_DIMENSION);
+    let height = src_h.next_multiple_of(2).max(MIN_DIMENSION);
+
+    // Map quality (1-100) to quantizer (255-40): higher quality = lower quantizer
+    let quality = quality.clamp(1, 100) as usize;
+    let quantizer = 255 - (quality - 1) * 215 / 99;
+
+    let enc = EncoderConfig {
+        width: width as usize,
+        height: height as usize,
+        time_base: Rational::new(1, fps as u64),
+        bit_depth: 8,
+        chroma_sampling: ChromaSampling::Cs420,
+        pixel_range: PixelRange::Limited,
+        color_description: Some(ColorDescription {
+            color_primaries: ColorPrimaries::BT601,
+            transfer_characteristics: TransferCharacteristics::BT601,
+            matrix_coefficients: MatrixCoefficients::BT601,
+        }),
+        quantizer,
+        min_key_frame_interval: 0,
+        max_key_frame_interval: (fps as u64 * 5).max(1),
+        speed_settings: SpeedSettings::from_preset(9),
+        ..Default::default()
+    };
+
+    let cfg = Config::new().with_encoder_config(enc).with_threads(0);
+    let mut ctx: Context<u8> = cfg.new_context().map_err(|e| e.to_string())?;
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut webm = WebmWriter::new(
+        BufWriter::new(file),
+        &ctx.container_sequence_header(),
+        width,
+        height,
+        duration_ms as f64,
+    )
+    .map_err(|e| e.to_string())?;
+
+    for i in 0..total {
+        let mut frame = ctx.new_frame();
+        let (y, u, v) = rgba_to_yuv420(&frames.frame(i)?, width, height);
+        let chroma_w = (width / 2) as usize;
+        frame.planes[0].copy_from_raw_u8(&y, width as usize, 1);
+        frame.planes[1].copy_from_raw_u8(&u, chroma_w, 1);
+        frame.planes[2].copy_from_raw_u8(&v, chroma_w, 1);
+
+        ctx.send_frame(frame).map_err(|e| e.to_string())?;
+        drain_packets(&mut ctx, &mut webm, &timestamps_ms, &mut on_progress)?;
+    }
+
+    ctx.flush();
+    drain_packets(&mut ctx, &mut webm, &timestamps_ms, &mut on_progress)?;
+
+    webm.finish()
+        .and_then(|mut out| out.flush())
+        .map_err(|e| e.to_string())
+}
+
+/// Pull every packet currently available from the encoder into the file
+fn drain_packets<W, F>(
+    ctx: &mut Context<u8>,
+    webm: &mut WebmWriter<W>,
+    timestamps_ms: &[u64],
+    on_progress: &mut F,
+) -> Result<(), String>
+where
+    W: Write + Seek,
+    F: FnMut(usize, usize) -> Result<(), String>,
+{
+    let total = timestamps_ms.len();
+    loop {
+        match ctx.receive_packet() {
+            Ok(pkt) => {
+                webm.write_packet(&Packet {
+                    timestamp_ms: timestamps_ms
+                        .get(pkt.input_frameno as usize)
+                        .copied()
+                        .unwrap_or_default(),
+                    keyframe: pkt.frame_type == FrameType::KEY,
+                    data: pkt.data,
+                })
+                .map_err(|e| e.to_string())?;
+                on_progress(webm.block_count.min(total), total)?;
+            }
+            Err(EncoderStatus::Encoded) => continue,
+            Err(EncoderStatus::NeedMoreData) | Err(EncoderStatus::LimitReached) => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Convert RGBA to planar BT.601 limited-range YUV 4:2:0. Where the target size
+/// exceeds the image (padded sources), the edge pixels repeat.
+fn rgba_to_yuv420(img: &RgbaImage, width: u32, height: u32) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let (w, h) = (width as usize, height as usize);
+    let (cw, ch) = (w / 2, h / 2);
+    let mut y_plane = vec![0u8; w * h];
+    let mut u_plane = vec![0u8; cw * ch];
+    let mut v_plane = vec![0u8; cw * ch];
+    let (max_x, max_y) = (
+        img.width().saturating_sub(1),
+        img.height().saturating_sub(1),
+    );
+    let pixel = |x: usize, y: usize| img.get_pixel((x as u32).min(max_x), (y as u32).min(max_y));
+
+    for py in 0..h {
+        for px in 0..w {
+            let p = pixel(px, py);
+            let (r, g, b) = (p[0] as f32, p[1] as f32, p[2] as f32);
+            y_plane[py * w + px] = (16.0 + 0.257 * r + 0.504 * g + 0.098 * b).round() as u8;
+        }
+    }
+
+    for cy in 0..ch {
+        for cx in 0..cw {
+            let (mut r, mut g, mut b) = (0.0f32, 0.0f32, 0.0f32);
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let p = pixel(cx * 2 + dx, cy * 2 + dy);
+                r += p[0] as f32;
+                g += p[1] as f32;
+                b += p[2] as f32;
+            }
+            let (r, g, b) = (r / 4.0, g / 4.0, b / 4.0);
+            u_plane[cy * cw + cx] = (128.0 - 0.148 * r - 0.291 * g + 0.439 * b)
+                .round()
+                .clamp(0.0, 255.0) as u8;
+            v_plane[cy * cw + cx] = (128.0 + 0.439 * r - 0.368 * g - 0.071 * b)
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    (y_plane, u_plane, v_plane)
+}
+
+// EBML element IDs used by the WebM muxer
+const EBML: u32 = 0x1A45_DFA3;
+const EBML_VERSION: u32 = 0x4286;
+const EBML_READ_VERSION: u32 = 0x42F7;
+const EBML_MAX_ID_LENGTH: u32 = 0x42F2;
+const EBML_MAX_SIZE_LENGTH: u32 = 0x42F3;
+const DOC_TYPE: u32 = 0x4282;
+const DOC_TYPE_VERSION: u32 = 0x4287;
+const DOC_TYPE_READ_VERSION: u32 = 0x4285;
+const SEGMENT: u32 = 0x1853_8067;
+const INFO: u32 = 0x1549_A966;
+const TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const DURATION: u32 = 0x4489;
+const MUXING_APP: u32 = 0x4D80;
+const WRITING_APP: u32 = 0x5741;
+const TRACKS: u32 = 0x1654_AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_NUMBER: u32 = 0xD7;
+const TRACK_UID: u32 = 0x73C5;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+const CODEC_PRIVATE: u32 = 0x63A2;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CLUSTER: u32 = 0x1F43_B675;
+const TIMECODE: u32 = 0xE7;
+const SIMPLE_BLOCK: u32 = 0xA3;
+
+/// Maximum block offset from its cluster timecode (SimpleBlock stores an i16)
+const MAX_CLUSTER_SPAN_MS: u64 = 30_000;
+
+/// Writes a WebM file (single AV1 video track, millisecond timecodes) while
+/// packets arrive. Only the open cluster is kept in memory; the segment size is
+/// patched in by `finish`.
+struct WebmWriter<W: Write + Seek> {
+    out: W,
+    segment_data_start: u64, // file offset right after the segment's size field
+    cluster: Vec<u8>,
+    cluster_start: u64,
+    block_count: usize,
+}
+
+impl<W: Write + Seek> WebmWriter<W> {
+    /// Write the EBML header, segment info and track entry
+    fn new(
+        mut out: W,
+        codec_private: &[u8],
+        width: u32,
+        height: u32,
+        duration_ms: f64,
+    ) -> io::Result<Self> {
+        let mut header = Vec::new();
+        ebml_uint(&mut header, EBML_VERSION, 1);
+        ebml_uint(&mut header, EBML_READ_VERSION, 1);
+        ebml_uint(&mut header, EBML_MAX_ID_LENGTH, 4);
+        ebml_uint(&mut header, EBML_MAX_SIZE_LENGTH, 8);
+        ebml_bytes(&mut header, DOC_TYPE, b"webm");
+        ebml_uint(&mut header, DOC_TYPE_VERSION, 4);
+        ebml_uint(&mut header, DOC_TYPE_READ_VERSION, 2);
+
+        let mut info = Vec::new();
+        ebml_uint(&mut info, TIMECODE_SCALE, 1_000_000);
+        ebml_float(&mut info, DURATION, duration_ms);
+        ebml_bytes(&mut info, MUXING_APP, b"lovshot");
+        ebml_bytes(&mut info, WRITING_APP, b"lovshot");
+
+        let mut video = Vec::new();
+        ebml_uint(&mut video, PIXEL_WIDTH, width as u64);
+        ebml_uint(&mut video, PIXEL_HEIGHT, height as u64);
+
+        let mut track = Vec::new();
+        ebml_uint(&mut track, TRACK_NUMBER, 1);
+        ebml_uint(&mut track, TRACK_UID, 1);
+        ebml_uint(&mut track, TRACK_TYPE, 1);
+        ebml_bytes(&mut track, CODEC_ID, b"V_AV1");
+        ebml_bytes(&mut track, CODEC_PRIVATE, codec_private);
+        ebml_bytes(&mut track, VIDEO, &video);
+
+        let mut tracks = Vec::new();
+        ebml_bytes(&mut tracks, TRACK_ENTRY, &track);
+
+        let mut start = Vec::new();
+        ebml_bytes(&mut start, EBML, &header);
+        ebml_id(&mut start, SEGMENT);
+        ebml_size(&mut start, 0); // patched by `finish`
+        let segment_data_start = out.stream_position()? + start.len() as u64;
+        ebml_bytes(&mut start, INFO, &info);
+        ebml_bytes(&mut start, TRACKS, &tracks);
+        out.write_all(&start)?;
+
+        Ok(Self {
+            out,
+            segment_data_start,
+            cluster: Vec::new(),
+            cluster_start: 0,
+            block_count: 0,
+        })
+    }
+
+    /// Add a packet, starting a new cluster at every keyframe or when block
+    /// offsets would overflow
+    fn write_packet(&mut self, pkt: &Packet) -> io::Result<()> {
+        let needs_new = self.cluster.is_empty()
+            || pkt.keyframe
+            || pkt.timestamp_ms - self.cluster_start > MAX_CLUSTER_SPAN_MS;
+        if needs_new {
+            self.flush_cluster()?;
+            self.cluster_start = pkt.timestamp_ms;
+            ebml_uint(&mut self.cluster, TIMECODE, self.cluster_start);
+        }
+
+        let mut block = Vec::with_capacity(pkt.data.len() + 4);
+        block.push(0x81); // track number 1 as a 1-byte vint
+        block.extend_from_slice(&((pkt.timestamp_ms - self.cluster_start) as i16).to_be_bytes());
+        block.push(if pkt.keyframe { 0x80 } else { 0x00 });
+        block.extend_from_slice(&pkt.data);
+        ebml_bytes(&mut self.cluster, SIMPLE_BLOCK, &block);
+        self.block_count += 1;
+        Ok(())
+    }
+
+    fn flush_cluster(&mut self) -> io::Result<()> {
+        if self.cluster.is_empty() {
+            return Ok(());
+        }
+        let mut head = Vec::with_capacity(12);
+        ebml_id(&mut head, CLUSTER);
+        ebml_size(&mut head, self.cluster.len() as u64);
+        self.out.write_all(&head)?;
+        self.out.write_all(&self.cluster)?;
+        self.cluster.clear();
+        Ok(())
+    }
+
+    /// Write the last cluster and the final segment size
+    fn finish(mut self) -> io::Result<W> {
+        self.flush_cluster()?;
+        let end = self.out.stream_position()?;
+        let mut size = Vec::with_capacity(8);
+        ebml_size(&mut size, end - self.segment_data_start);
+        self.out
+            .seek(SeekFrom::Start(self.segment_data_start - 8))?;
+        self.out.write_all(&size)?;
+        self.out.seek(SeekFrom::Start(end))?;
+        Ok(self.out)
+    }
+}
+
+fn ebml_id(out: &mut Vec<u8>, id: u32) {
+    let bytes = id.to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    out.extend_from_slice(&bytes[skip..]);
+}
+
+/// Write an element size as a fixed 8-byte vint
+fn ebml_size(out: &mut Vec<u8>, size: u64) {
+    out.push(0x01);
+    out.extend_from_slice(&size.to_be_bytes()[1..]);
+}
+
+fn ebml_bytes(out: &mut Vec<u8>, id: u32, data: &[u8]) {
+    ebml_id(out, id);
+    ebml_size(out, data.len() as u64);
+    out.extend_from_slice(data);
+}
+
+fn ebml_uint(out: &mut Vec<u8>, id: u32, value: u64) {
+    let bytes = value.to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count().min(7);
+    ebml_bytes(out, id, &bytes[skip..]);
+}
+
+fn ebml_float(out: &mut Vec<u8>, id: u32, value: f64) {
+    ebml_bytes(out, id, &value.to_be_bytes());
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use core_foundation::base::{CFType, TCFType};
 use core_foundation::dictionary::CFDictionaryRef;
 use core_foundation::number::CFNumber;
@@ -334,6 +336,211 @@ fn get_app_name_from_pid(pid: i32) -> Option<String> {
     }
 }
 
+/// Get bundle identifier (e.g. "com.apple.Safari") from PID
+fn get_bundle_id_from_pid(pid: i32) -> Option<String> {
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let workspace_class = class!(NSRunningApplication);
+        let running_app: *mut objc::runtime::Object = msg_send![
+            workspace_class,
+            runningApplicationWithProcessIdentifier: pid
+        ];
+
+        if running_app.is_null() {
+            return None;
+        }
+
+        let bundle_id: *mut objc::runtime::Object = msg_send![running_app, bundleIdentifier];
+        if bundle_id.is_null() {
+            return None;
+        }
+
+        let utf8: *const std::os::raw::c_char = msg_send![bundle_id, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+
+        Some(
+            std::ffi::CStr::from_ptr(utf8)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+/// Visible parts of all on-screen windows owned by any of `apps` (app names or
+/// bundle IDs, case-insensitive), at any layer so notifications and popups are
+/// included. For normal (layer 0) windows, areas covered by other apps' opaque
+/// normal windows are left out, so whatever is on top stays in the capture.
+/// Other layers (Dock, menu bar, overlays) report bounds that don't match what
+/// they draw, so they never count as covering; excluded windows on those
+/// layers are masked whole.
+pub fn get_visible_windows_of_apps(apps: &[String]) -> Vec<Region> {
+    let apps: Vec<String> = apps.iter().map(|a| a.to_lowercase()).collect();
+    let matches = |id: &str| apps.iter().any(|a| *a == id.to_lowercase());
+    let mut regions = Vec::new();
+    if apps.is_empty() {
+        return regions;
+    }
+
+    unsafe {
+        let window_list =
+            CGWindowListCopyWindowInfo(kCGWindowListOptionOnScreenOnly, kCGNullWindowID);
+
+        if window_list.is_null() {
+            return regions;
+        }
+
+        let windows: core_foundation::array::CFArray<CFType> =
+            core_foundation::array::CFArray::wrap_under_get_rule(window_list as _);
+
+        // Several windows usually belong to the same app
+        let mut bundle_ids: HashMap<i32, Option<String>> = HashMap::new();
+        // The list runs front to back; opaque normal windows seen so far cover
+        // normal windows further back
+        let mut covering: Vec<Region> = Vec::new();
+        let own_pid = std::process::id() as i32;
+
+        for i in 0..windows.len() {
+            let Some(window) = windows.get(i) else {
+                continue;
+            };
+            let dict_ref = window.as_CFTypeRef() as CFDictionaryRef;
+
+            let owner_key = CFString::new("kCGWindowOwnerName");
+            let owner_ptr = core_foundation::dictionary::CFDictionaryGetValue(
+                dict_ref,
+                owner_key.as_CFTypeRef() as *const _,
+            );
+            let owner_matches = !owner_ptr.is_null()
+                && matches(&CFString::wrap_under_get_rule(owner_ptr as _).to_string());
+
+            let pid_key = CFString::new("kCGWindowOwnerPID");
+            let pid = get_number_from_dict(dict_ref, &pid_key).map(|p| p as i32);
+            let excluded = owner_matches
+                || pid.is_some_and(|pid| {
+                    bundle_ids
+                        .entry(pid)
+                        .or_insert_with(|| get_bundle_id_from_pid(pid))
+                        .as_deref()
+                        .is_some_and(matches)
+                });
+
+            let layer_key = CFString::new("kCGWindowLayer");
+            let layer = get_number_from_dict(dict_ref, &layer_key).unwrap_or(0.0) as i32;
+            // Our own overlays and translucent windows don't hide anything reliably
+            let alpha_key = CFString::new("kCGWindowAlpha");
+            let opaque = get_number_from_dict(dict_ref, &alpha_key).is_none_or(|a| a >= 1.0);
+            let covers = layer == 0 && opaque && pid != Some(own_pid);
+            if !excluded && !covers {
+                continue;
+            }
+
+            // Get window bounds
+            let bounds_key = CFString::new("kCGWindowBounds");
+            let bounds_ptr = core_foundation::dictionary::CFDictionaryGetValue(
+                dict_ref,
+                bounds_key.as_CFTypeRef() as *const _,
+            );
+
+            if bounds_ptr.is_null() {
+                continue;
+            }
+
+            let bounds_dict = bounds_ptr as CFDictionaryRef;
+
+            let x_key = CFString::new("X");
+            let y_key = CFString::new("Y");
+            let width_key = CFString::new("Width");
+            let height_key = CFString::new("Height");
+
+            let Some(win_x) = get_number_from_dict(bounds_dict, &x_key) else {
+                continue;
+            };
+            let Some(win_y) = get_number_from_dict(bounds_dict, &y_key) else {
+                continue;
+            };
+            let Some(win_w) = get_number_from_dict(bounds_dict, &width_key) else {
+                continue;
+            };
+            let Some(win_h) = get_number_from_dict(bounds_dict, &height_key) else {
+                continue;
+            };
+
+            if win_w <= 0.0 || win_h <= 0.0 {
+                continue;
+            }
+            let bounds = Region {
+                x: win_x as i32,
+                y: win_y as i32,
+                width: win_w as u32,
+                height: win_h as u32,
+            };
+            if excluded && layer == 0 {
+                regions.extend(subtract_regions(bounds, &covering));
+            } else if excluded {
+                regions.push(bounds);
+            } else {
+                covering.push(bounds);
+            }
+        }
+    }
+
+    regions
+}
+
+/// What's left of `area` after removing every region in `cover`, as
+/// non-overlapping rectangles
+fn subtract_regions(area: Region, cover: &[Region]) -> Vec<Region> {
+    let mut pieces = vec![area];
+    for c in cover {
+        pieces = pieces
+            .into_iter()
+            .flat_map(|piece| subtract_region(piece, c))
+            .collect();
+        if pieces.is_empty() {
+            break;
+        }
+    }
+    pieces
+}
+
+/// `area` minus `c`: up to four bands around the overlap
+fn subtract_region(area: Region, c: &Region) -> Vec<Region> {
+    let edges = |r: &Region| {
+        let (x, y) = (r.x as i64, r.y as i64);
+        (x, y, x + r.width as i64, y + r.height as i64)
+    };
+    let (left, top, right, bottom) = edges(&area);
+    let (c_left, c_top, c_right, c_bottom) = edges(c);
+    if c_left >= right || c_right <= left || c_top >= bottom || c_bottom <= top {
+        return vec![area];
+    }
+
+    let band = |l: i64, t: i64, r: i64, b: i64| Region {
+        x: l as i32,
+        y: t as i32,
+        width: (r - l) as u32,
+        height: (b - t) as u32,
+    };
+    let mut pieces = Vec::new();
+    if c_top > top {
+        pieces.push(band(left, top, right, c_top));
+    }
+    if c_bottom < bottom {
+        pieces.push(band(left, c_bottom, right, bottom));
+    }
+    let (mid_top, mid_bottom) = (c_top.max(top), c_bottom.min(bottom));
+    if c_left > left {
+        pieces.push(band(left, mid_top, c_left, mid_bottom));
+    }
+    if c_right < right {
+        pieces.push(band(c_right, mid_top, right, mid_bottom));
+    }
+    pieces
+}
+
 /// Get titlebar height based on app name presets + AX fallback
 fn get_titlebar_height_for_window(pid: i32, win_bounds: (f64, f64, f64, f64)) -> u32 {
     let app_name = get_app_name_from_pid(pid);
@@ -837,3 +1044,49 @@ pub fn activate_window_at_position(x: f64, y: f64) -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: i32, y: i32, width: u32, height: u32) -> Region {
+        Region {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn area(regions: &[Region]) -> u64 {
+        regions
+            .iter()
+            .map(|r| r.width as u64 * r.height as u64)
+            .sum()
+    }
+
+    #[test]
+    fn subtract_full_screen_cover_leaves_nothing() {
+        let window = region(100, 100, 800, 600);
+        let screen = region(0, 0, 2560, 1440);
+        assert!(subtract_regions(window, &[screen]).is_empty());
+    }
+
+    #[test]
+    fn subtract_partial_covers() {
+        let window = region(0, 0, 100, 100);
+        let hole = subtract_regions(window.clone(), &[region(20, 20, 30, 30)]);
+        assert_eq!(area(&hole), 100 * 100 - 30 * 30);
+
+        let covers = [region(50, -10, 100, 200), region(0, 80, 100, 50)];
+        let left = subtract_regions(window.clone(), &covers);
+        assert_eq!(left.len(), 1);
+        assert_eq!(
+            (left[0].x, left[0].y, left[0].width, left[0].height),
+            (0, 0, 50, 80)
+        );
+
+        let untouched = subtract_regions(window, &[region(200, 0, 10, 10)]);
+        assert_eq!(area(&untouched), 100 * 100);
+    }
+}
@@ -1,4 +1,8 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::commands::release_session;
+use crate::state::SharedState;
+use crate::types::CaptureMode;
 
 /// Set macOS activation policy
 /// policy: 0 = Regular (normal app, shows in Dock when windows open)
@@ -50,8 +54,13 @@ pub fn open_settings_window(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Open the GIF editor window
-pub fn open_editor_window(app: &AppHandle) -> Result<(), String> {
+/// Open an editor window for the recording `session_id`. The window is bound to
+/// that session and unloads it when closed.
+pub fn open_editor_window(
+    app: &AppHandle,
+    mode: CaptureMode,
+    session_id: &str,
+) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         use objc::{class, msg_send, sel, sel_impl};
@@ -69,8 +78,12 @@ pub fn open_editor_window(app: &AppHandle) -> Result<(), String> {
         .as_millis();
     let window_label = format!("editor-{}", timestamp);
 
-    let win = WebviewWindowBuilder::new(app, &window_label, WebviewUrl::App("/editor.html".into()))
-        .title("Lovshot GIF Editor")
+    let url = format!("/editor.html?session={}", session_id);
+    let win = WebviewWindowBuilder::new(app, &window_label, WebviewUrl::App(url.into()))
+        .title(match mode {
+            CaptureMode::Video => "Lovshot Video Editor",
+            _ => "Lovshot GIF Editor",
+        })
         .inner_size(360.0, 620.0)
         .min_inner_size(320.0, 400.0)
         .resizable(true)
@@ -79,6 +92,14 @@ pub fn open_editor_window(app: &AppHandle) -> Result<(), String> {
         .build()
         .map_err(|e| e.to_string())?;
 
+    let app_handle = app.clone();
+    let session_id = session_id.to_string();
+    win.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            release_session(&app_handle.state::<SharedState>(), &session_id);
+        }
+    });
+
     let _ = win.show();
     let _ = win.set_focus();
 
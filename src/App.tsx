@@ -7,7 +7,7 @@ import "./App.css";
 interface HistoryItem {
   path: string;
   filename: string;
-  file_type: "screenshot" | "gif";
+  file_type: "screenshot" | "gif" | "video";
   modified: number;
   size: number;
   thumbnail: string;
@@ -30,6 +30,7 @@ interface Stats {
   total_count: number;
   screenshot_count: number;
   gif_count: number;
+  video_count: number;
   total_size: number;
   today_count: number;
   week_count: number;
@@ -58,6 +59,14 @@ function formatDate(timestamp: number): string {
   return date.toLocaleDateString("zh-CN", { month: "short", day: "numeric" });
 }
 
+// Animated captures share the "gif" type; label them by actual format
+function animatedLabel(filename: string): string {
+  const ext = filename.split(".").pop()?.toLowerCase();
+  if (ext === "png") return "APNG";
+  if (ext === "webp") return "WEBP";
+  return "GIF";
+}
+
 function App() {
   const [history, setHistory] = useState<HistoryItem[]>([]);
   const [hasMore, setHasMore] = useState(false);
@@ -150,11 +159,12 @@ function App() {
       const { success, path } = event.payload;
       if (!success || !path) return;
       const filename = path.split("/").pop() || path;
+      const fileType = filename.toLowerCase().endsWith(".webm") ? "video" : "gif";
       // 插入 loading 占位符
       const placeholder: HistoryItem = {
         path,
         filename,
-        file_type: "gif",
+        file_type: fileType,
         modified: Math.floor(Date.now() / 1000),
         size: 0,
         thumbnail: "",
@@ -168,7 +178,7 @@ function App() {
           const res = await invoke<HistoryResponse>("get_history", {
             offset: 0,
             limit: 1,
-            filterType: "gif",
+            filterType: fileType,
           });
           if (res.items.length > 0 && res.items[0].path === path) {
             setHistory((prev) =>
@@ -337,11 +347,13 @@ function App() {
                       <div className="history-thumb-loading">
                         <div className="loading-spinner" />
                       </div>
+                    ) : item.file_type === "video" ? (
+                      <video src={convertFileSrc(item.path)} className="history-thumb" muted preload="metadata" />
                     ) : (
                       <img src={convertFileSrc(item.path)} alt={item.filename} className="history-thumb" loading="lazy" />
                     )}
                     <span className={`history-badge history-badge-${item.file_type}`}>
-                      {item.file_type === "gif" ? "GIF" : "IMG"}
+                      {item.file_type === "gif" ? animatedLabel(item.filename) : item.file_type === "video" ? "VIDEO" : "IMG"}
                     </span>
                   </div>
                 ))}
@@ -363,11 +375,20 @@ function App() {
           {selected ? (
             <>
               <div className="preview-image-container">
-                <img
-                  src={convertFileSrc(selected.path)}
-                  alt={selected.filename}
-                  className="preview-full-image"
-                />
+                {selected.file_type === "video" ? (
+                  <video
+                    src={convertFileSrc(selected.path)}
+                    className="preview-full-image"
+                    controls
+                    loop
+                  />
+                ) : (
+                  <img
+                    src={convertFileSrc(selected.path)}
+                    alt={selected.filename}
+                    className="preview-full-image"
+                  />
+                )}
               </div>
               <div className="preview-info">
                 <h3 className="preview-filename">{selected.filename}</h3>
@@ -377,7 +398,7 @@ function App() {
                   <span>{formatDate(selected.modified)}</span>
                   <span>·</span>
                   <span className={`preview-type preview-type-${selected.file_type}`}>
-                    {selected.file_type === "gif" ? "GIF" : "Screenshot"}
+                    {selected.file_type === "gif" ? animatedLabel(selected.filename) : selected.file_type === "video" ? "Video" : "Screenshot"}
                   </span>
                 </div>
                 <div className="preview-actions">
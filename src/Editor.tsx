@@ -5,14 +5,43 @@ import { save } from "@tauri-apps/plugin-dialog";
 import "./App.css";
 
 interface RecordingInfo {
+  session_id: string;
   frame_count: number;
   width: number;
   height: number;
   fps: number;
   duration_ms: number;
   has_frames: boolean;
+  mode: "gif" | "video";
+  stop_reason: "user" | "max_duration" | "max_disk";
+  timelapse_interval_ms: number | null;
 }
 
+// Recording this window edits; set by the backend when it opens the window
+const SESSION_ID = new URLSearchParams(window.location.search).get("session") ?? "";
+
+// Wall time → output time for timelapse recordings
+const TIMELAPSE_SPEEDS: [number, string][] = [
+  [10, "10 s → 1 s"],
+  [30, "30 s → 1 s"],
+  [60, "1 min → 1 s"],
+  [120, "2 min → 1 s"],
+  [300, "5 min → 1 s"],
+  [600, "10 min → 1 s"],
+];
+
+// Per-clip playback speeds offered for regular recordings
+const CLIP_SPEEDS = [0.25, 0.5, 1, 1.5, 2, 3, 4, 8];
+
+// How long "Hold" freezes the last selected frame
+const HOLD_MS = 2000;
+
+const STOP_REASON_NOTICES: Record<RecordingInfo["stop_reason"], string | null> = {
+  user: null,
+  max_duration: "Recording stopped automatically: max duration reached",
+  max_disk: "Recording stopped automatically: disk space limit for frames reached",
+};
+
 interface ExportConfig {
   start_frame: number;
   end_frame: number;
@@ -20,14 +49,177 @@ interface ExportConfig {
   target_fps: number;
   loop_mode: string;
   quality: number;
-  speed: number;
   output_path: string | null;
+  format: ExportFormat;
+  palette: GifPalette;
+  quantizer: GifQuantizer;
+  dither: GifDither;
+  max_bytes: number | null;
+  show_cursor: boolean;
+  highlight_clicks: boolean;
+  keystrokes: KeystrokeBadge;
+  crop: CropRect | null;
+}
+
+// Part of the recorded frames to export, in recorded pixels
+interface CropRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+type CropPreset = "none" | "16:9" | "1:1" | "4:3" | "custom";
+
+const CROP_ASPECTS: Partial<Record<CropPreset, number>> = {
+  "16:9": 16 / 9,
+  "1:1": 1,
+  "4:3": 4 / 3,
+};
+
+// Largest centered rect of the given aspect ratio
+function fitAspect(width: number, height: number, aspect: number): CropRect {
+  const w = Math.min(width, Math.round(height * aspect));
+  const h = Math.min(height, Math.round(w / aspect));
+  return { x: Math.floor((width - w) / 2), y: Math.floor((height - h) / 2), width: w, height: h };
+}
+
+// A run of source frames in the edited timeline
+interface Clip {
+  start: number;
+  end: number;
+  reversed: boolean;
+  speed: number;
+  hold_ms: number | null;
+}
+
+type EditOp =
+  | { op: "delete"; start: number; end: number }
+  | { op: "duplicate"; start: number; end: number }
+  | { op: "reverse"; start: number; end: number }
+  | { op: "speed"; start: number; end: number; speed: number }
+  | { op: "hold"; frame: number; duration_ms: number };
+
+interface EditState {
+  clips: Clip[];
+  frame_count: number;
+  duration_ms: number;
+  can_undo: boolean;
+  can_redo: boolean;
+}
+
+type Rgba8 = [number, number, number, number];
+
+// Drawn on export; positions and sizes in recorded pixels
+type Annotation =
+  | { kind: "text"; x: number; y: number; text: string; font_size: number; color: Rgba8; background: Rgba8 | null }
+  | { kind: "rect"; x: number; y: number; width: number; height: number; color: Rgba8; stroke_width: number; fill: Rgba8 | null }
+  | { kind: "arrow"; from_x: number; from_y: number; to_x: number; to_y: number; color: Rgba8; stroke_width: number };
+
+// Shown while source frames start_frame..end_frame are on screen
+type TimedAnnotation = Annotation & { start_frame: number; end_frame: number };
+
+const ANNOTATION_FIELDS: Record<Annotation["kind"], string[]> = {
+  text: ["x", "y", "font_size"],
+  rect: ["x", "y", "width", "height"],
+  arrow: ["from_x", "from_y", "to_x", "to_y"],
+};
+
+const ANNOTATION_COLOR: Rgba8 = [255, 59, 48, 255];
+
+type RedactionMode = "pixelate" | "blur" | "solid";
+
+// Scrubbed from every export while source frames start_frame..end_frame are on screen
+interface TimedRedaction {
+  start_frame: number;
+  end_frame: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  mode: RedactionMode;
+}
+
+const REDACTION_FIELDS = ["x", "y", "width", "height"] as const;
+
+function toHex([r, g, b]: Rgba8): string {
+  return "#" + [r, g, b].map((c) => c.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(hex: string, alpha: number): Rgba8 {
+  const n = parseInt(hex.slice(1), 16);
+  return [(n >> 16) & 255, (n >> 8) & 255, n & 255, alpha];
+}
+
+// Source frames shown in edited frames start..end, as one range
+function sourceRange(clips: Clip[], start: number, end: number): [number, number] {
+  let offset = 0;
+  let lo = Infinity;
+  let hi = -Infinity;
+  for (const clip of clips) {
+    const len = clip.end - clip.start;
+    const from = Math.max(start, offset) - offset;
+    const to = Math.min(end, offset + len) - offset;
+    if (from < to) {
+      lo = Math.min(lo, clip.reversed ? clip.end - to : clip.start + from);
+      hi = Math.max(hi, clip.reversed ? clip.end - from : clip.start + to);
+    }
+    offset += len;
+  }
+  return lo < hi ? [lo, hi] : [start, end];
+}
+
+type BadgePosition = "top_left" | "top_center" | "top_right" | "bottom_left" | "bottom_center" | "bottom_right";
+
+interface KeystrokeBadge {
+  enabled: boolean;
+  position: BadgePosition;
+  font_size: number; // recorded frame pixels
+  fade_ms: number;
 }
 
+interface KeyEvent {
+  timestamp_ms: number;
+  label: string;
+}
+
+const DEFAULT_KEYSTROKE_BADGE: KeystrokeBadge = {
+  enabled: true,
+  position: "bottom_center",
+  font_size: 32,
+  fade_ms: 500,
+};
+
+const BADGE_POSITIONS: [BadgePosition, string][] = [
+  ["bottom_center", "Bottom"],
+  ["bottom_left", "Bottom left"],
+  ["bottom_right", "Bottom right"],
+  ["top_center", "Top"],
+  ["top_left", "Top left"],
+  ["top_right", "Top right"],
+];
+
+const BADGE_SIZES = [20, 32, 48, 64];
+
+type ExportFormat = "gif" | "apng" | "webp" | "png_sequence" | "zip";
+type GifPalette = "per_frame" | "global";
+type GifQuantizer = "neuquant" | "median_cut";
+type GifDither = "none" | "ordered" | "floyd_steinberg";
+
+const FORMAT_FILTERS: Record<ExportFormat, { name: string; ext: string }> = {
+  gif: { name: "GIF", ext: "gif" },
+  apng: { name: "APNG", ext: "png" },
+  webp: { name: "WebP", ext: "webp" },
+  // Saved as a folder of numbered PNGs, so no extension
+  png_sequence: { name: "PNG Sequence", ext: "" },
+  zip: { name: "ZIP", ext: "zip" },
+};
+
 interface SizeEstimate {
   frame_count: number;
   output_width: number;
   output_height: number;
+  duration_ms: number;
   estimated_bytes: number;
   formatted: string;
 }
@@ -36,9 +228,37 @@ interface SaveResult {
   success: boolean;
   path: string | null;
   error: string | null;
+  export_params: ExportParams | null;
+  job_id: number | null;
 }
 
+interface ExportParams {
+  quality: number;
+  output_scale: number;
+  target_fps: number;
+  bytes: number;
+  attempts: number;
+}
+
+const EXPORT_CANCELLED = "Export cancelled";
+
+type SheetLabel = "none" | "frame_number" | "timestamp";
+
+// Contact sheet grids (columns × rows)
+const SHEET_GRIDS = [
+  [3, 2],
+  [3, 3],
+  [4, 3],
+  [4, 4],
+  [6, 4],
+];
+const SHEET_MAX_WIDTH = 2048;
+
+const MB = 1024 * 1024;
+const MAX_SIZE_PRESETS = [1, 5, 8, 10, 25];
+
 interface ExportProgress {
+  job_id: number;
   current: number;
   total: number;
   stage: string;
@@ -50,6 +270,10 @@ interface ResolutionPreset {
   scale: number;
 }
 
+function nearestTimelapseSpeed(speed: number): number {
+  return TIMELAPSE_SPEEDS.reduce((best, [s]) => (Math.abs(s - speed) < Math.abs(best - speed) ? s : best), 10);
+}
+
 export default function Editor() {
   const [recordingInfo, setRecordingInfo] = useState<RecordingInfo | null>(null);
   const [exportConfig, setExportConfig] = useState<ExportConfig>({
@@ -59,14 +283,34 @@ export default function Editor() {
     target_fps: 10,
     loop_mode: "infinite",
     quality: 80,
-    speed: 1,
     output_path: null,
+    format: "gif",
+    palette: "per_frame",
+    quantizer: "neuquant",
+    dither: "none",
+    max_bytes: null,
+    show_cursor: true,
+    highlight_clicks: false,
+    keystrokes: DEFAULT_KEYSTROKE_BADGE,
+    crop: null,
   });
+  const [keystrokes, setKeystrokes] = useState<KeyEvent[]>([]);
+  const [edits, setEdits] = useState<EditState | null>(null);
+  const [annotations, setAnnotations] = useState<TimedAnnotation[]>([]);
+  const [redactions, setRedactions] = useState<TimedRedaction[]>([]);
+  const [cropPreset, setCropPreset] = useState<CropPreset>("none");
   const [sizeEstimate, setSizeEstimate] = useState<SizeEstimate | null>(null);
   const [filmstrip, setFilmstrip] = useState<string[]>([]);
   const [exporting, setExporting] = useState(false);
   const [exportProgress, setExportProgress] = useState<ExportProgress | null>(null);
   const [savedPath, setSavedPath] = useState("");
+  const [exportParams, setExportParams] = useState<ExportParams | null>(null);
+  const [exportJobId, setExportJobId] = useState<number | null>(null);
+  const [sheetGrid, setSheetGrid] = useState(2);
+  const [sheetLabel, setSheetLabel] = useState<SheetLabel>("timestamp");
+  const exportJobRef = useRef<number | null>(null);
+  // Results that arrived before export_gif returned their job ID
+  const earlyResultsRef = useRef(new Map<number, SaveResult>());
 
   const filmstripRef = useRef<HTMLDivElement>(null);
   const [dragging, setDragging] = useState<"start" | "end" | null>(null);
@@ -74,10 +318,14 @@ export default function Editor() {
   const [previewFrame, setPreviewFrame] = useState<number | null>(null);
   const [previewImage, setPreviewImage] = useState<string | null>(null);
 
+  // Frames in the edited timeline; the filmstrip, selection and preview index into it
+  const frameCount = edits?.frame_count ?? recordingInfo?.frame_count ?? 0;
+
   const resolutionPresets = useMemo<ResolutionPreset[]>(() => {
     if (!recordingInfo) return [];
 
-    const { width, height } = recordingInfo;
+    // Scaling applies to the cropped frame
+    const { width, height } = exportConfig.crop ?? recordingInfo;
     const presets: ResolutionPreset[] = [
       { label: `原始 (${width}×${height})`, height, scale: 1 },
     ];
@@ -103,11 +351,11 @@ export default function Editor() {
     }
 
     return presets;
-  }, [recordingInfo]);
+  }, [recordingInfo, exportConfig.crop]);
 
   const updateSizeEstimate = useCallback(async (config: ExportConfig) => {
     try {
-      const estimate = await invoke<SizeEstimate>("estimate_export_size", { config });
+      const estimate = await invoke<SizeEstimate>("estimate_export_size", { sessionId: SESSION_ID, config });
       setSizeEstimate(estimate);
     } catch (e) {
       console.error("估算体积失败:", e);
@@ -118,7 +366,7 @@ export default function Editor() {
   useEffect(() => {
     const loadRecordingInfo = async () => {
       try {
-        const info = await invoke<RecordingInfo>("get_recording_info");
+        const info = await invoke<RecordingInfo>("get_recording_info", { sessionId: SESSION_ID });
         setRecordingInfo(info);
         const initialConfig: ExportConfig = {
           start_frame: 0,
@@ -127,15 +375,27 @@ export default function Editor() {
           target_fps: 10,
           loop_mode: "infinite",
           quality: 80,
-          speed: 1,
           output_path: null,
+          format: "gif",
+          palette: "per_frame",
+          quantizer: "neuquant",
+          dither: "none",
+          max_bytes: null,
+          show_cursor: true,
+          highlight_clicks: false,
+          keystrokes: DEFAULT_KEYSTROKE_BADGE,
+          crop: null,
         };
         setExportConfig(initialConfig);
+        invoke<KeyEvent[]>("get_keystrokes", { sessionId: SESSION_ID }).then(setKeystrokes);
+        invoke<EditState>("get_edits", { sessionId: SESSION_ID }).then(setEdits);
+        invoke<TimedAnnotation[]>("get_annotations", { sessionId: SESSION_ID }).then(setAnnotations);
+        invoke<TimedRedaction[]>("get_redactions", { sessionId: SESSION_ID }).then(setRedactions);
         setPreviewFrame(0);
         updateSizeEstimate(initialConfig);
 
         if (info.frame_count > 0) {
-          invoke<string[]>("get_filmstrip", { count: 12, thumbHeight: 40 })
+          invoke<string[]>("get_filmstrip", { sessionId: SESSION_ID, count: 12, thumbHeight: 40 })
             .then(setFilmstrip)
             .catch((e) => console.error("加载filmstrip失败:", e));
         }
@@ -147,19 +407,35 @@ export default function Editor() {
     loadRecordingInfo();
   }, [updateSizeEstimate]);
 
-  // Listen for export events
+  const applyExportResult = useCallback((result: SaveResult) => {
+    exportJobRef.current = null;
+    setExportJobId(null);
+    setExporting(false);
+    setExportProgress(null);
+    // Exports burn pending redactions into the recording, which empties the list
+    invoke<TimedRedaction[]>("get_redactions", { sessionId: SESSION_ID }).then(setRedactions);
+    if (result.success && result.path) {
+      setSavedPath(result.path);
+      setExportParams(result.export_params);
+    } else if (result.error && result.error !== EXPORT_CANCELLED) {
+      console.error("导出失败:", result.error);
+    }
+  }, []);
+
+  // Listen for export events (only those of this window's current job)
   useEffect(() => {
     const unlistenExport = listen<SaveResult>("export-complete", (event) => {
-      setExporting(false);
-      setExportProgress(null);
-      if (event.payload.success && event.payload.path) {
-        setSavedPath(event.payload.path);
-      } else if (event.payload.error) {
-        console.error("导出失败:", event.payload.error);
+      const jobId = event.payload.job_id;
+      if (jobId === null) return;
+      if (jobId !== exportJobRef.current) {
+        earlyResultsRef.current.set(jobId, event.payload);
+        return;
       }
+      applyExportResult(event.payload);
     });
 
     const unlistenProgress = listen<ExportProgress>("export-progress", (event) => {
+      if (event.payload.job_id !== exportJobRef.current) return;
       setExportProgress(event.payload);
     });
 
@@ -167,31 +443,108 @@ export default function Editor() {
       unlistenExport.then((fn) => fn());
       unlistenProgress.then((fn) => fn());
     };
-  }, []);
+  }, [applyExportResult]);
 
   useEffect(() => {
     if (recordingInfo) {
       updateSizeEstimate(exportConfig);
     }
-  }, [exportConfig, recordingInfo, updateSizeEstimate]);
+  }, [exportConfig, recordingInfo, edits, updateSizeEstimate]);
+
+  const isVideo = recordingInfo?.mode === "video";
+  const selectionRange = { start: exportConfig.start_frame, end: exportConfig.end_frame };
 
-  const handleExport = async () => {
+  // Apply, undo or redo an edit
+  const runEdit = async (command: "apply_edit" | "undo_edit" | "redo_edit", op?: EditOp) => {
     try {
+      const state = await invoke<EditState>(command, op ? { sessionId: SESSION_ID, op } : { sessionId: SESSION_ID });
+      setEdits(state);
+      // Keep the selection unless the edit changed which frames it covers
+      if (state.frame_count !== frameCount) {
+        setExportConfig((c) => ({ ...c, start_frame: 0, end_frame: state.frame_count }));
+      }
+      setPreviewFrame((f) => (f === null ? null : Math.min(f, state.frame_count - 1)));
+      invoke<string[]>("get_filmstrip", { sessionId: SESSION_ID, count: 12, thumbHeight: 40 })
+        .then(setFilmstrip)
+        .catch((e) => console.error("加载filmstrip失败:", e));
+    } catch (e) {
+      console.error("编辑失败:", e);
+    }
+  };
+
+  const applySpeed = (speed: number) => runEdit("apply_edit", { op: "speed", ...selectionRange, speed });
+
+  // Speed of the clip at the start of the selection
+  const selectionSpeed = useMemo(() => {
+    let offset = 0;
+    for (const clip of edits?.clips ?? []) {
+      offset += clip.end - clip.start;
+      if (exportConfig.start_frame < offset) return clip.speed;
+    }
+    return 1;
+  }, [edits, exportConfig.start_frame]);
+
+  // Ask for a destination, then start an export job via `command`
+  const startExport = async (
+    command: string,
+    filter: { name: string; ext: string },
+    prefix: string,
+    config: object,
+  ) => {
+    try {
+      const stamp = new Date().toISOString().replace(/[:.]/g, "").slice(0, 15);
       const path = await save({
-        defaultPath: `recording_${new Date().toISOString().replace(/[:.]/g, "").slice(0, 15)}.gif`,
-        filters: [{ name: "GIF", extensions: ["gif"] }],
+        defaultPath: filter.ext ? `${prefix}_${stamp}.${filter.ext}` : `${prefix}_${stamp}`,
+        filters: filter.ext ? [{ name: filter.name, extensions: [filter.ext] }] : [],
       });
 
       if (!path) return;
 
       setExporting(true);
-      await invoke("export_gif", { config: { ...exportConfig, output_path: path } });
+      setExportParams(null);
+      const jobId = await invoke<number>(command, {
+        sessionId: SESSION_ID,
+        config: { ...config, output_path: path },
+      });
+      exportJobRef.current = jobId;
+      setExportJobId(jobId);
+
+      const early = earlyResultsRef.current.get(jobId);
+      if (early) {
+        earlyResultsRef.current.delete(jobId);
+        applyExportResult(early);
+      }
     } catch (e) {
       console.error("导出失败:", e);
       setExporting(false);
     }
   };
 
+  const handleExport = () =>
+    startExport(
+      isVideo ? "export_video" : "export_gif",
+      isVideo ? { name: "WebM", ext: "webm" } : FORMAT_FILTERS[exportConfig.format],
+      "recording",
+      exportConfig,
+    );
+
+  const handleExportSheet = () => {
+    const [columns, rows] = SHEET_GRIDS[sheetGrid];
+    startExport("export_contact_sheet", { name: "PNG", ext: "png" }, "contact_sheet", {
+      start_frame: exportConfig.start_frame,
+      end_frame: exportConfig.end_frame,
+      frame_count: columns * rows,
+      columns,
+      label: sheetLabel,
+      max_width: SHEET_MAX_WIDTH,
+    });
+  };
+
+  const handleCancelExport = () => {
+    if (exportJobId === null) return;
+    invoke("cancel_export", { jobId: exportJobId }).catch((e) => console.error("取消导出失败:", e));
+  };
+
   useEffect(() => {
     if (previewFrame === null || !recordingInfo) {
       setPreviewImage(null);
@@ -199,21 +552,37 @@ export default function Editor() {
     }
 
     let cancelled = false;
-    invoke<string>("get_frame_thumbnail", { frameIndex: previewFrame, maxHeight: 200 })
+    invoke<string>("get_frame_thumbnail", {
+      sessionId: SESSION_ID,
+      frameIndex: previewFrame,
+      maxHeight: 200,
+      crop: exportConfig.crop,
+    })
       .then((img) => {
         if (!cancelled) setPreviewImage(img);
       })
       .catch((e) => console.error("Failed to get preview:", e));
 
     return () => { cancelled = true; };
-  }, [previewFrame, recordingInfo]);
+  }, [previewFrame, recordingInfo, edits, annotations, redactions, exportConfig.crop]);
+
+  // The filmstrip shows annotations and redactions too; refresh once typing settles
+  useEffect(() => {
+    if (!recordingInfo || recordingInfo.frame_count === 0) return;
+    const timer = setTimeout(() => {
+      invoke<string[]>("get_filmstrip", { sessionId: SESSION_ID, count: 12, thumbHeight: 40 })
+        .then(setFilmstrip)
+        .catch((e) => console.error("加载filmstrip失败:", e));
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [recordingInfo, annotations, redactions]);
 
   const getFrameFromX = useCallback((clientX: number): number => {
     if (!filmstripRef.current || !recordingInfo) return 0;
     const rect = filmstripRef.current.getBoundingClientRect();
     const ratio = Math.max(0, Math.min(1, (clientX - rect.left) / rect.width));
-    return Math.round(ratio * recordingInfo.frame_count);
-  }, [recordingInfo]);
+    return Math.round(ratio * frameCount);
+  }, [recordingInfo, frameCount]);
 
   const handleFilmstripMouseDown = useCallback((e: React.MouseEvent, handle: "start" | "end") => {
     e.preventDefault();
@@ -224,8 +593,8 @@ export default function Editor() {
   const handleFilmstripHover = useCallback((e: React.MouseEvent) => {
     if (dragging) return; // Don't interfere with handle dragging
     const frame = getFrameFromX(e.clientX);
-    setPreviewFrame(Math.min(frame, (recordingInfo?.frame_count ?? 1) - 1));
-  }, [getFrameFromX, recordingInfo, dragging]);
+    setPreviewFrame(Math.min(frame, frameCount - 1));
+  }, [getFrameFromX, frameCount, dragging]);
 
   useEffect(() => {
     if (!dragging || !recordingInfo) return;
@@ -236,7 +605,7 @@ export default function Editor() {
         const newStart = Math.max(0, Math.min(frame, exportConfig.end_frame - 1));
         setExportConfig((c) => ({ ...c, start_frame: newStart }));
       } else {
-        const newEnd = Math.min(recordingInfo.frame_count, Math.max(frame, exportConfig.start_frame + 1));
+        const newEnd = Math.min(frameCount, Math.max(frame, exportConfig.start_frame + 1));
         setExportConfig((c) => ({ ...c, end_frame: newEnd }));
       }
     };
@@ -249,22 +618,121 @@ export default function Editor() {
       window.removeEventListener("mousemove", handleMouseMove);
       window.removeEventListener("mouseup", handleMouseUp);
     };
-  }, [dragging, recordingInfo, exportConfig.start_frame, exportConfig.end_frame, getFrameFromX]);
+  }, [dragging, recordingInfo, frameCount, exportConfig.start_frame, exportConfig.end_frame, getFrameFromX]);
+
+  // Edits go straight to the backend so every export uses the edited log
+  const updateKeystrokes = useCallback((events: KeyEvent[]) => {
+    setKeystrokes(events);
+    invoke("set_keystrokes", { sessionId: SESSION_ID, events }).catch(console.error);
+  }, []);
+
+  const updateAnnotations = useCallback((list: TimedAnnotation[]) => {
+    setAnnotations(list);
+    invoke("set_annotations", { sessionId: SESSION_ID, annotations: list }).catch(console.error);
+  }, []);
+
+  // New annotations cover the selected frames, placed relative to the recording size
+  const addAnnotation = (kind: Annotation["kind"]) => {
+    if (!recordingInfo) return;
+    const { width, height } = recordingInfo;
+    const [start_frame, end_frame] = sourceRange(edits?.clips ?? [], exportConfig.start_frame, exportConfig.end_frame);
+    const stroke_width = Math.max(2, Math.round(height / 150));
+    const shapes: Record<Annotation["kind"], Annotation> = {
+      text: {
+        kind: "text",
+        x: Math.round(width * 0.05),
+        y: Math.round(height * 0.05),
+        text: "Caption",
+        font_size: Math.max(12, Math.round(height / 15)),
+        color: [255, 255, 255, 255],
+        background: [0, 0, 0, 180],
+      },
+      rect: {
+        kind: "rect",
+        x: Math.round(width * 0.25),
+        y: Math.round(height * 0.25),
+        width: Math.round(width * 0.5),
+        height: Math.round(height * 0.5),
+        color: ANNOTATION_COLOR,
+        stroke_width,
+        fill: null,
+      },
+      arrow: {
+        kind: "arrow",
+        from_x: Math.round(width * 0.8),
+        from_y: Math.round(height * 0.2),
+        to_x: Math.round(width * 0.5),
+        to_y: Math.round(height * 0.5),
+        color: ANNOTATION_COLOR,
+        stroke_width,
+      },
+    };
+    updateAnnotations([...annotations, { ...shapes[kind], start_frame, end_frame }]);
+  };
+
+  const updateAnnotation = (index: number, changes: object) =>
+    updateAnnotations(annotations.map((a, i) => (i === index ? ({ ...a, ...changes } as TimedAnnotation) : a)));
+
+  const updateRedactions = useCallback((list: TimedRedaction[]) => {
+    setRedactions(list);
+    invoke("set_redactions", { sessionId: SESSION_ID, redactions: list }).catch(console.error);
+  }, []);
+
+  // New redactions cover the selected frames, centered in the recording
+  const addRedaction = () => {
+    if (!recordingInfo) return;
+    const { width, height } = recordingInfo;
+    const [start_frame, end_frame] = sourceRange(edits?.clips ?? [], exportConfig.start_frame, exportConfig.end_frame);
+    updateRedactions([
+      ...redactions,
+      {
+        start_frame,
+        end_frame,
+        x: Math.round(width * 0.35),
+        y: Math.round(height * 0.35),
+        width: Math.round(width * 0.3),
+        height: Math.round(height * 0.3),
+        mode: "pixelate",
+      },
+    ]);
+  };
+
+  const updateRedaction = (index: number, changes: Partial<TimedRedaction>) =>
+    updateRedactions(redactions.map((r, i) => (i === index ? { ...r, ...changes } : r)));
+
+  // Crop changes reset the output scale, whose presets depend on the cropped size
+  const setCrop = (crop: CropRect | null) => setExportConfig((c) => ({ ...c, crop, output_scale: 1 }));
+
+  const handleCropPreset = (preset: CropPreset) => {
+    if (!recordingInfo) return;
+    setCropPreset(preset);
+    const aspect = CROP_ASPECTS[preset];
+    if (preset === "none") {
+      setCrop(null);
+    } else if (aspect) {
+      setCrop(fitAspect(recordingInfo.width, recordingInfo.height, aspect));
+    } else if (!exportConfig.crop) {
+      setCrop({ x: 0, y: 0, width: recordingInfo.width, height: recordingInfo.height });
+    }
+  };
+
+  // Exact pixel edits keep the rect inside the recording
+  const updateCropField = (field: keyof CropRect, value: number) => {
+    if (!recordingInfo || !exportConfig.crop || Number.isNaN(value)) return;
+    const crop = { ...exportConfig.crop, [field]: Math.max(0, Math.round(value)) };
+    crop.x = Math.min(crop.x, recordingInfo.width - 1);
+    crop.y = Math.min(crop.y, recordingInfo.height - 1);
+    crop.width = Math.max(1, Math.min(crop.width, recordingInfo.width - crop.x));
+    crop.height = Math.max(1, Math.min(crop.height, recordingInfo.height - crop.y));
+    setCropPreset("custom");
+    setCrop(crop);
+  };
 
   const formatDuration = (ms: number) => {
     const seconds = ms / 1000;
     return seconds.toFixed(1) + "s";
   };
 
-  const getPlaybackDuration = useCallback((frameCount: number) => {
-    if (!recordingInfo || recordingInfo.fps <= 0) return 0;
-    const originalDurationSec = frameCount / recordingInfo.fps;
-    return (originalDurationSec / exportConfig.speed) * 1000;
-  }, [recordingInfo, exportConfig.speed]);
-
-  const trimmedFrameCount = exportConfig.end_frame - exportConfig.start_frame;
-  const trimmedDuration = getPlaybackDuration(trimmedFrameCount);
-
   if (!recordingInfo) {
     return (
       <main className="container">
@@ -280,11 +748,15 @@ export default function Editor() {
     <main className="container">
       <div className="controls">
         <div className="editor">
+          {STOP_REASON_NOTICES[recordingInfo.stop_reason] && (
+            <div className="stop-notice">{STOP_REASON_NOTICES[recordingInfo.stop_reason]}</div>
+          )}
+
           {previewImage && (
             <div className="preview-container">
               <img src={previewImage} alt="Preview" className="preview-image" draggable={false} />
               <span className="preview-frame-info">
-                Frame {previewFrame} / {recordingInfo.frame_count - 1}
+                Frame {previewFrame} / {frameCount - 1}
               </span>
             </div>
           )}
@@ -302,32 +774,174 @@ export default function Editor() {
               </div>
               <div
                 className="filmstrip-mask filmstrip-mask-left"
-                style={{ width: `${(exportConfig.start_frame / recordingInfo.frame_count) * 100}%` }}
+                style={{ width: `${(exportConfig.start_frame / frameCount) * 100}%` }}
               />
               <div
                 className="filmstrip-mask filmstrip-mask-right"
-                style={{ width: `${((recordingInfo.frame_count - exportConfig.end_frame) / recordingInfo.frame_count) * 100}%` }}
+                style={{ width: `${((frameCount - exportConfig.end_frame) / frameCount) * 100}%` }}
               />
               {previewFrame !== null && (
                 <div
                   className="filmstrip-playhead"
-                  style={{ left: `${(previewFrame / recordingInfo.frame_count) * 100}%` }}
+                  style={{ left: `${(previewFrame / frameCount) * 100}%` }}
                 />
               )}
               <div
                 className="filmstrip-handle filmstrip-handle-start"
-                style={{ left: `${(exportConfig.start_frame / recordingInfo.frame_count) * 100}%` }}
+                style={{ left: `${(exportConfig.start_frame / frameCount) * 100}%` }}
                 onMouseDown={(e) => handleFilmstripMouseDown(e, "start")}
               />
               <div
                 className="filmstrip-handle filmstrip-handle-end"
-                style={{ left: `${(exportConfig.end_frame / recordingInfo.frame_count) * 100}%` }}
+                style={{ left: `${(exportConfig.end_frame / frameCount) * 100}%` }}
                 onMouseDown={(e) => handleFilmstripMouseDown(e, "end")}
               />
             </div>
           </div>
 
           <div className="editor-controls">
+            <div className="control-row">
+              <label>Edit</label>
+              <div className="edit-buttons">
+                <button
+                  className="edit-button"
+                  onClick={() => runEdit("apply_edit", { op: "delete", ...selectionRange })}
+                  disabled={selectionRange.end - selectionRange.start >= frameCount}
+                  title="Cut the selected frames"
+                >
+                  Delete
+                </button>
+                <button
+                  className="edit-button"
+                  onClick={() => runEdit("apply_edit", { op: "duplicate", ...selectionRange })}
+                  title="Repeat the selected frames after the selection"
+                >
+                  Duplicate
+                </button>
+                <button
+                  className="edit-button"
+                  onClick={() => runEdit("apply_edit", { op: "reverse", ...selectionRange })}
+                  title="Play the selected frames backwards"
+                >
+                  Reverse
+                </button>
+                <button
+                  className="edit-button"
+                  onClick={() =>
+                    runEdit("apply_edit", { op: "hold", frame: selectionRange.end - 1, duration_ms: HOLD_MS })
+                  }
+                  title="Freeze the last selected frame"
+                >
+                  Hold {HOLD_MS / 1000}s
+                </button>
+                <button className="edit-button" onClick={() => runEdit("undo_edit")} disabled={!edits?.can_undo}>
+                  Undo
+                </button>
+                <button className="edit-button" onClick={() => runEdit("redo_edit")} disabled={!edits?.can_redo}>
+                  Redo
+                </button>
+              </div>
+            </div>
+
+            {!isVideo && (
+              <div className="control-row">
+                <label>Format</label>
+                <select
+                  value={exportConfig.format}
+                  onChange={(e) => setExportConfig((c) => ({ ...c, format: e.target.value as ExportFormat }))}
+                >
+                  <option value="gif">GIF</option>
+                  <option value="apng">APNG</option>
+                  <option value="webp">WebP</option>
+                  <option value="png_sequence">PNG Sequence</option>
+                  <option value="zip">ZIP (PNG)</option>
+                </select>
+              </div>
+            )}
+
+            {!isVideo && exportConfig.format === "gif" && (
+              <>
+                <div className="control-row">
+                  <label>Palette</label>
+                  <select
+                    value={exportConfig.palette}
+                    onChange={(e) => setExportConfig((c) => ({ ...c, palette: e.target.value as GifPalette }))}
+                  >
+                    <option value="per_frame">Per frame</option>
+                    <option value="global">Global</option>
+                  </select>
+                </div>
+
+                <div className="control-row">
+                  <label>Quantizer</label>
+                  <select
+                    value={exportConfig.quantizer}
+                    onChange={(e) => setExportConfig((c) => ({ ...c, quantizer: e.target.value as GifQuantizer }))}
+                  >
+                    <option value="neuquant">NeuQuant</option>
+                    <option value="median_cut">Median cut</option>
+                  </select>
+                </div>
+
+                <div className="control-row">
+                  <label>Dither</label>
+                  <select
+                    value={exportConfig.dither}
+                    onChange={(e) => setExportConfig((c) => ({ ...c, dither: e.target.value as GifDither }))}
+                  >
+                    <option value="none">None</option>
+                    <option value="ordered">Ordered</option>
+                    <option value="floyd_steinberg">Floyd–Steinberg</option>
+                  </select>
+                </div>
+              </>
+            )}
+
+            <div className="control-row">
+              <label>Cursor</label>
+              <select
+                value={`${exportConfig.show_cursor}-${exportConfig.highlight_clicks}`}
+                onChange={(e) => {
+                  const [cursor, clicks] = e.target.value.split("-");
+                  setExportConfig((c) => ({ ...c, show_cursor: cursor === "true", highlight_clicks: clicks === "true" }));
+                }}
+              >
+                <option value="true-false">Show</option>
+                <option value="true-true">Show + clicks</option>
+                <option value="false-true">Clicks only</option>
+                <option value="false-false">Hide</option>
+              </select>
+            </div>
+
+            <div className="control-row">
+              <label>Crop</label>
+              <select value={cropPreset} onChange={(e) => handleCropPreset(e.target.value as CropPreset)}>
+                <option value="none">None</option>
+                <option value="16:9">16:9</option>
+                <option value="1:1">1:1</option>
+                <option value="4:3">4:3</option>
+                <option value="custom">Custom</option>
+              </select>
+            </div>
+
+            {exportConfig.crop && (
+              <div className="control-row">
+                <label>Crop Rect</label>
+                <div className="range-inputs">
+                  {(["x", "y", "width", "height"] as const).map((field) => (
+                    <input
+                      key={field}
+                      type="number"
+                      min="0"
+                      title={field}
+                      value={exportConfig.crop![field]}
+                      onChange={(e) => updateCropField(field, parseInt(e.target.value))}
+                    />
+                  ))}
+                </div>
+              </div>
+            )}
+
             <div className="control-row">
               <label>Resolution</label>
               <select
@@ -372,20 +986,115 @@ export default function Editor() {
               </div>
             </div>
 
-            <div className="control-row">
-              <label>Speed</label>
-              <div className="speed-slider">
-                <input
-                  type="range"
-                  min="0.1"
-                  max="10"
-                  step="0.1"
-                  value={exportConfig.speed}
-                  onChange={(e) => setExportConfig((c) => ({ ...c, speed: parseFloat(e.target.value) }))}
-                />
-                <span className="speed-value">{exportConfig.speed.toFixed(1)}×</span>
+            {recordingInfo.timelapse_interval_ms ? (
+              <div className="control-row">
+                <label>Timelapse</label>
+                <select
+                  value={nearestTimelapseSpeed(selectionSpeed)}
+                  onChange={(e) => applySpeed(parseFloat(e.target.value))}
+                >
+                  {TIMELAPSE_SPEEDS.map(([speed, label]) => (
+                    <option key={speed} value={speed}>{label}</option>
+                  ))}
+                </select>
               </div>
-            </div>
+            ) : (
+              <div className="control-row">
+                <label>Speed</label>
+                <select value={selectionSpeed} onChange={(e) => applySpeed(parseFloat(e.target.value))}>
+                  {!CLIP_SPEEDS.includes(selectionSpeed) && (
+                    <option value={selectionSpeed}>{selectionSpeed.toFixed(2)}×</option>
+                  )}
+                  {CLIP_SPEEDS.map((speed) => (
+                    <option key={speed} value={speed}>{speed}×</option>
+                  ))}
+                </select>
+              </div>
+            )}
+
+            {!isVideo && exportConfig.format !== "png_sequence" && (
+              <div className="control-row">
+                <label>Max Size</label>
+                <select
+                  value={exportConfig.max_bytes ?? ""}
+                  onChange={(e) =>
+                    setExportConfig((c) => ({
+                      ...c,
+                      max_bytes: e.target.value ? parseInt(e.target.value) : null,
+                    }))
+                  }
+                >
+                  <option value="">No limit</option>
+                  {MAX_SIZE_PRESETS.map((mb) => (
+                    <option key={mb} value={mb * MB}>
+                      {mb} MB
+                    </option>
+                  ))}
+                </select>
+              </div>
+            )}
+
+            {!isVideo && (
+              <div className="control-row">
+                <label>Sheet</label>
+                <select value={sheetGrid} onChange={(e) => setSheetGrid(parseInt(e.target.value))}>
+                  {SHEET_GRIDS.map(([columns, rows], i) => (
+                    <option key={i} value={i}>
+                      {columns}×{rows}
+                    </option>
+                  ))}
+                </select>
+                <select value={sheetLabel} onChange={(e) => setSheetLabel(e.target.value as SheetLabel)}>
+                  <option value="timestamp">Time</option>
+                  <option value="frame_number">Frame #</option>
+                  <option value="none">No label</option>
+                </select>
+              </div>
+            )}
+
+            {keystrokes.length > 0 && (
+              <div className="control-row">
+                <label>Keys</label>
+                <select
+                  value={exportConfig.keystrokes.enabled ? exportConfig.keystrokes.position : "off"}
+                  onChange={(e) => {
+                    const value = e.target.value;
+                    setExportConfig((c) => ({
+                      ...c,
+                      keystrokes: value === "off"
+                        ? { ...c.keystrokes, enabled: false }
+                        : { ...c.keystrokes, enabled: true, position: value as BadgePosition },
+                    }));
+                  }}
+                >
+                  {BADGE_POSITIONS.map(([position, label]) => (
+                    <option key={position} value={position}>{label}</option>
+                  ))}
+                  <option value="off">Off</option>
+                </select>
+                <select
+                  value={exportConfig.keystrokes.font_size}
+                  onChange={(e) =>
+                    setExportConfig((c) => ({ ...c, keystrokes: { ...c.keystrokes, font_size: parseInt(e.target.value) } }))
+                  }
+                >
+                  {BADGE_SIZES.map((size) => (
+                    <option key={size} value={size}>{size}px</option>
+                  ))}
+                </select>
+                <select
+                  value={exportConfig.keystrokes.fade_ms}
+                  onChange={(e) =>
+                    setExportConfig((c) => ({ ...c, keystrokes: { ...c.keystrokes, fade_ms: parseInt(e.target.value) } }))
+                  }
+                >
+                  <option value={0}>No fade</option>
+                  <option value={250}>Fade 0.25s</option>
+                  <option value={500}>Fade 0.5s</option>
+                  <option value={1000}>Fade 1s</option>
+                </select>
+              </div>
+            )}
 
             <div className="control-row">
               <label>Loop</label>
@@ -400,11 +1109,136 @@ export default function Editor() {
             </div>
           </div>
 
+          <details className="annotation-log">
+            <summary>Annotations ({annotations.length})</summary>
+            <ul>
+              {annotations.map((a, i) => (
+                <li key={i}>
+                  <span className="keystroke-time" title="Source frames">
+                    {a.start_frame}–{a.end_frame}
+                  </span>
+                  {a.kind === "text" ? (
+                    <input value={a.text} onChange={(e) => updateAnnotation(i, { text: e.target.value })} />
+                  ) : (
+                    <span className="annotation-kind">{a.kind === "rect" ? "Box" : "Arrow"}</span>
+                  )}
+                  {ANNOTATION_FIELDS[a.kind].map((field) => (
+                    <input
+                      key={field}
+                      className="annotation-field"
+                      type="number"
+                      title={field}
+                      value={(a as unknown as Record<string, number>)[field]}
+                      onChange={(e) => {
+                        const value = parseFloat(e.target.value);
+                        if (!Number.isNaN(value)) updateAnnotation(i, { [field]: value });
+                      }}
+                    />
+                  ))}
+                  <input
+                    className="annotation-color"
+                    type="color"
+                    value={toHex(a.color)}
+                    onChange={(e) => updateAnnotation(i, { color: fromHex(e.target.value, a.color[3]) })}
+                  />
+                  <button
+                    className="keystroke-remove"
+                    onClick={() => updateAnnotations(annotations.filter((_, j) => j !== i))}
+                    title="Remove"
+                  >
+                    ×
+                  </button>
+                </li>
+              ))}
+            </ul>
+            <div className="annotation-add">
+              <button className="edit-button" onClick={() => addAnnotation("text")}>+ Caption</button>
+              <button className="edit-button" onClick={() => addAnnotation("rect")}>+ Box</button>
+              <button className="edit-button" onClick={() => addAnnotation("arrow")}>+ Arrow</button>
+            </div>
+          </details>
+
+          <details className="annotation-log">
+            <summary>Redactions ({redactions.length})</summary>
+            <p className="annotation-note">
+              Burned into the recording for good when you export or close the editor
+            </p>
+            <ul>
+              {redactions.map((r, i) => (
+                <li key={i}>
+                  <span className="keystroke-time" title="Source frames">
+                    {r.start_frame}–{r.end_frame}
+                  </span>
+                  <select
+                    value={r.mode}
+                    onChange={(e) => updateRedaction(i, { mode: e.target.value as RedactionMode })}
+                  >
+                    <option value="pixelate">Pixelate</option>
+                    <option value="blur">Blur</option>
+                    <option value="solid">Solid</option>
+                  </select>
+                  {REDACTION_FIELDS.map((field) => (
+                    <input
+                      key={field}
+                      className="annotation-field"
+                      type="number"
+                      title={field}
+                      value={r[field]}
+                      onChange={(e) => {
+                        const value = parseFloat(e.target.value);
+                        if (!Number.isNaN(value)) updateRedaction(i, { [field]: value } as Partial<TimedRedaction>);
+                      }}
+                    />
+                  ))}
+                  <button
+                    className="keystroke-remove"
+                    onClick={() => updateRedactions(redactions.filter((_, j) => j !== i))}
+                    title="Remove"
+                  >
+                    ×
+                  </button>
+                </li>
+              ))}
+            </ul>
+            <div className="annotation-add">
+              <button className="edit-button" onClick={addRedaction}>+ Redact area</button>
+            </div>
+          </details>
+
+          {keystrokes.length > 0 && (
+            <details className="keystroke-log">
+              <summary>Keystrokes ({keystrokes.length})</summary>
+              <ul>
+                {keystrokes.map((key, i) => (
+                  <li key={i}>
+                    <span className="keystroke-time">{formatDuration(key.timestamp_ms)}</span>
+                    <input
+                      value={key.label}
+                      onChange={(e) =>
+                        updateKeystrokes(keystrokes.map((k, j) => (j === i ? { ...k, label: e.target.value } : k)))
+                      }
+                    />
+                    <button
+                      className="keystroke-remove"
+                      onClick={() => updateKeystrokes(keystrokes.filter((_, j) => j !== i))}
+                      title="Remove"
+                    >
+                      ×
+                    </button>
+                  </li>
+                ))}
+              </ul>
+              <button className="btn-open" onClick={() => updateKeystrokes([])}>
+                Remove all
+              </button>
+            </details>
+          )}
+
           {sizeEstimate && (
             <div className="size-estimate">
               <span>{sizeEstimate.output_width}×{sizeEstimate.output_height}</span>
               <span className="size-sep">·</span>
-              <span>{formatDuration(trimmedDuration)}</span>
+              <span>{formatDuration(sizeEstimate.duration_ms)}</span>
               <span className="size-sep">·</span>
               <span>{sizeEstimate.frame_count}f</span>
               <span className="size-sep">·</span>
@@ -412,6 +1246,18 @@ export default function Editor() {
             </div>
           )}
 
+          {exportParams && exportParams.attempts > 1 && (
+            <div className="size-estimate">
+              <span>Fitted after {exportParams.attempts} tries</span>
+              <span className="size-sep">·</span>
+              <span>Q{exportParams.quality}</span>
+              <span className="size-sep">·</span>
+              <span>{Math.round(exportParams.output_scale * 100)}%</span>
+              <span className="size-sep">·</span>
+              <span>{exportParams.target_fps} fps</span>
+            </div>
+          )}
+
           <div className="export-actions">
             <button
               className="btn-primary btn-export"
@@ -422,6 +1268,7 @@ export default function Editor() {
                 exportProgress ? (
                   <>
                     <span className="export-progress-text">
+                      {exportProgress.stage !== "encoding" && `${exportProgress.stage} · `}
                       {Math.round((exportProgress.current / exportProgress.total) * 100)}%
                     </span>
                     <span
@@ -433,9 +1280,19 @@ export default function Editor() {
                   "Exporting..."
                 )
               ) : (
-                "Export GIF"
+                isVideo ? "Export Video" : `Export ${FORMAT_FILTERS[exportConfig.format].name}`
               )}
             </button>
+            {!isVideo && !exporting && (
+              <button className="btn-open" onClick={handleExportSheet} title="Export a contact sheet PNG">
+                Sheet
+              </button>
+            )}
+            {exporting && exportJobId !== null && (
+              <button className="btn-open" onClick={handleCancelExport}>
+                Cancel
+              </button>
+            )}
             {savedPath && (
               <button
                 className="btn-open"
@@ -1,4 +1,4 @@
-import { useEffect, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
@@ -48,6 +48,9 @@ function matchesAnyShortcut(e: KeyboardEvent, shortcuts: ShortcutConfig[]): bool
 export default function RecordingOverlay() {
   const [region, setRegion] = useState<OverlayRegion | null>(null);
   const [isStatic, setIsStatic] = useState(false);
+  const [paused, setPaused] = useState(false);
+  const [countdown, setCountdown] = useState<number | null>(null); // seconds until a timed screenshot
+  const pausedRef = useRef(false);
 
   useEffect(() => {
     // Get region from window label query params or listen for it
@@ -57,27 +60,42 @@ export default function RecordingOverlay() {
     const w = parseInt(params.get("w") || "200");
     const h = parseInt(params.get("h") || "200");
     const staticMode = params.get("static") === "1";
+    const countdownParam = params.get("countdown");
     setRegion({ x, y, width: w, height: h });
     setIsStatic(staticMode);
+    if (countdownParam) setCountdown(parseInt(countdownParam));
+
+    // Timed screenshots tick every second; the backend closes the overlay before capturing
+    const unlistenCountdown = listen<number>("capture-countdown", (event) => {
+      setCountdown(event.payload);
+    });
 
     // Listen for recording stop to close (for GIF recording)
     const unlistenRecording = listen("recording-stopped", async () => {
       await getCurrentWindow().close();
     });
 
+    const unlistenPaused = listen<{ paused: boolean }>("recording-paused", (event) => {
+      pausedRef.current = event.payload.paused;
+      setPaused(event.payload.paused);
+    });
+
     // Listen for scroll capture stop to close (for scroll capture)
     const unlistenScroll = listen("scroll-capture-stop", async () => {
       await getCurrentWindow().close();
     });
 
-    // Load stop_recording shortcut config and listen for it
+    // Load stop_recording / pause_recording shortcut config and listen for them
     let handleKeyDown: ((e: KeyboardEvent) => void) | null = null;
 
     invoke<AppConfig>("get_shortcuts_config").then((config) => {
       const stopShortcuts = config.shortcuts["stop_recording"] || [];
-      if (stopShortcuts.length > 0) {
+      const pauseShortcuts = config.shortcuts["pause_recording"] || [];
+      if (stopShortcuts.length > 0 || pauseShortcuts.length > 0) {
         handleKeyDown = async (e: KeyboardEvent) => {
-          if (matchesAnyShortcut(e, stopShortcuts)) {
+          if (matchesAnyShortcut(e, pauseShortcuts)) {
+            await invoke(pausedRef.current ? "resume_recording" : "pause_recording");
+          } else if (matchesAnyShortcut(e, stopShortcuts)) {
             await invoke("stop_recording");
           }
         };
@@ -87,7 +105,9 @@ export default function RecordingOverlay() {
 
     return () => {
       unlistenRecording.then((fn) => fn());
+      unlistenPaused.then((fn) => fn());
       unlistenScroll.then((fn) => fn());
+      unlistenCountdown.then((fn) => fn());
       if (handleKeyDown) {
         document.removeEventListener("keydown", handleKeyDown);
       }
@@ -98,10 +118,26 @@ export default function RecordingOverlay() {
 
   const cornerLen = 20;
   const borderWidth = 3;
-  const cornerClass = isStatic ? "corner static" : "corner";
+  const cornerClass = isStatic ? "corner static" : paused ? "corner paused" : "corner";
 
   return (
     <div className="recording-overlay">
+      {countdown !== null && (
+        <div
+          className="countdown-badge"
+          style={{ left: region.x - borderWidth, top: Math.max(0, region.y - borderWidth - 30) }}
+        >
+          Capturing in {countdown}s
+        </div>
+      )}
+      {paused && (
+        <div
+          className="paused-badge"
+          style={{ left: region.x - borderWidth, top: Math.max(0, region.y - borderWidth - 26) }}
+        >
+          Paused
+        </div>
+      )}
       {/* Top-left corner - outside */}
       <div
         className={cornerClass}
@@ -8,6 +8,7 @@ import "./scroll-overlay.css";
 
 interface ScrollCaptureProgress {
   frame_count: number;
+  total_width: number;
   total_height: number;
   preview_base64: string;
 }
@@ -21,10 +22,21 @@ interface CropEdges {
 
 type Edge = "top" | "bottom" | "left" | "right";
 
+// Area to pixelate, as a percentage of the stitched image (like the crop edges)
+interface RedactionBox {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
 export default function ScrollOverlay() {
   const [progress, setProgress] = useState<ScrollCaptureProgress | null>(null);
   const [isStopped, setIsStopped] = useState(false);
   const [crop, setCrop] = useState<CropEdges>({ top: 0, bottom: 0, left: 0, right: 0 });
+  const [redacting, setRedacting] = useState(false);
+  const [redactions, setRedactions] = useState<RedactionBox[]>([]);
+  const [redactionDraft, setRedactionDraft] = useState<RedactionBox | null>(null);
   const containerRef = useRef<HTMLDivElement>(null);
   const draggingRef = useRef<Edge | null>(null);
 
@@ -107,11 +119,40 @@ export default function ScrollOverlay() {
 
   const startDrag = (edge: Edge) => (e: React.MouseEvent) => {
     e.preventDefault();
+    e.stopPropagation();
     draggingRef.current = edge;
     document.addEventListener("mousemove", handleMouseMove);
     document.addEventListener("mouseup", handleMouseUp);
   };
 
+  // Drag out a redaction box on the preview while redacting
+  const startRedaction = (e: React.MouseEvent) => {
+    if (!redacting || !containerRef.current) return;
+    e.preventDefault();
+    const rect = containerRef.current.getBoundingClientRect();
+    const toPct = (ev: { clientX: number; clientY: number }): [number, number] => [
+      Math.max(0, Math.min(100, ((ev.clientX - rect.left) / rect.width) * 100)),
+      Math.max(0, Math.min(100, ((ev.clientY - rect.top) / rect.height) * 100)),
+    ];
+    const [x0, y0] = toPct(e);
+    let box: RedactionBox = { x: x0, y: y0, width: 0, height: 0 };
+    setRedactionDraft(box);
+
+    const onMove = (ev: MouseEvent) => {
+      const [x, y] = toPct(ev);
+      box = { x: Math.min(x0, x), y: Math.min(y0, y), width: Math.abs(x - x0), height: Math.abs(y - y0) };
+      setRedactionDraft(box);
+    };
+    const onUp = () => {
+      document.removeEventListener("mousemove", onMove);
+      document.removeEventListener("mouseup", onUp);
+      setRedactionDraft(null);
+      if (box.width > 0.5 && box.height > 0.5) setRedactions((list) => [...list, box]);
+    };
+    document.addEventListener("mousemove", onMove);
+    document.addEventListener("mouseup", onUp);
+  };
+
   const handleStop = async () => {
     await invoke("stop_scroll_capture");
     setIsStopped(true);
@@ -122,6 +163,19 @@ export default function ScrollOverlay() {
     return crop;
   };
 
+  // Redactions are applied in stitched-image pixels, before the crop
+  const getRedactionsParam = () => {
+    if (!progress || redactions.length === 0) return null;
+    const { total_width, total_height } = progress;
+    return redactions.map((r) => ({
+      x: (r.x / 100) * total_width,
+      y: (r.y / 100) * total_height,
+      width: (r.width / 100) * total_width,
+      height: (r.height / 100) * total_height,
+      mode: "pixelate",
+    }));
+  };
+
   const handleFinish = async () => {
     try {
       const timestamp = new Date().toISOString().replace(/[:.]/g, "-").slice(0, 19);
@@ -133,7 +187,11 @@ export default function ScrollOverlay() {
       if (!filePath) return;
 
       await getCurrentWindow().hide();
-      await invoke<string>("finish_scroll_capture", { path: filePath, crop: getCropParam() });
+      await invoke<string>("finish_scroll_capture", {
+        path: filePath,
+        crop: getCropParam(),
+        redactions: getRedactionsParam(),
+      });
       await getCurrentWindow().destroy();
     } catch (e) {
       console.error("[ScrollOverlay] handleFinish error:", e);
@@ -142,7 +200,7 @@ export default function ScrollOverlay() {
 
   const handleCopy = async () => {
     try {
-      await invoke("copy_scroll_to_clipboard", { crop: getCropParam() });
+      await invoke("copy_scroll_to_clipboard", { crop: getCropParam(), redactions: getRedactionsParam() });
     } catch (e) {
       console.error("[ScrollOverlay] copy error:", e);
     }
@@ -186,11 +244,27 @@ export default function ScrollOverlay() {
 
       <div className="scroll-overlay-preview">
         {progress && (
-          <div className="crop-wrapper" ref={containerRef}>
+          <div
+            className={`crop-wrapper ${redacting ? "redacting" : ""}`}
+            ref={containerRef}
+            onMouseDown={startRedaction}
+          >
             <img src={progress.preview_base64} alt="" draggable={false} />
 
             {isStopped && (
               <>
+                {/* Redactions; click one to remove it */}
+                {[...redactions, ...(redactionDraft ? [redactionDraft] : [])].map((r, i) => (
+                  <div
+                    key={i}
+                    className="redaction-box"
+                    style={{ left: `${r.x}%`, top: `${r.y}%`, width: `${r.width}%`, height: `${r.height}%` }}
+                    onMouseDown={(e) => e.stopPropagation()}
+                    onClick={() => setRedactions((list) => list.filter((_, j) => j !== i))}
+                    title="Click to remove"
+                  />
+                ))}
+
                 {/* Crop overlays */}
                 <div className="crop-overlay crop-top" style={{ height: `${crop.top}%` }} />
                 <div className="crop-overlay crop-bottom" style={{ height: `${crop.bottom}%` }} />
@@ -219,6 +293,13 @@ export default function ScrollOverlay() {
           <button className="btn-stop" onClick={handleStop}>Stop</button>
         ) : (
           <>
+            <button
+              className={`btn-redact ${redacting ? "active" : ""}`}
+              onClick={() => setRedacting(!redacting)}
+              title="Drag on the preview to pixelate an area"
+            >
+              Redact
+            </button>
             <button className="btn-copy" onClick={handleCopy}>Copy</button>
             <button className="btn-save" onClick={handleFinish}>Save</button>
           </>
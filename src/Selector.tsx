@@ -12,6 +12,168 @@ interface SelectionRect {
   h: number;
 }
 
+// Timelapse capture intervals; 0 = regular recording at the configured fps
+const TIMELAPSE_INTERVALS: [number, string][] = [
+  [0, "Live"],
+  [1000, "1s"],
+  [2000, "2s"],
+  [5000, "5s"],
+  [10000, "10s"],
+  [30000, "30s"],
+];
+
+// Screenshot delays, to set up hover states, menus and tooltips; 0 = capture now
+const TIMER_OPTIONS: [number, string][] = [
+  [0, "No timer"],
+  [3, "3s"],
+  [5, "5s"],
+  [10, "10s"],
+];
+
+type Rgba8 = [number, number, number, number];
+
+// Screenshot annotations, in selection pixels; burned in by save_screenshot
+type Annotation =
+  | { kind: "text"; x: number; y: number; text: string; font_size: number; color: Rgba8; background: Rgba8 | null }
+  | { kind: "rect"; x: number; y: number; width: number; height: number; color: Rgba8; stroke_width: number; fill: Rgba8 | null }
+  | { kind: "ellipse"; x: number; y: number; width: number; height: number; color: Rgba8; stroke_width: number; fill: Rgba8 | null }
+  | { kind: "arrow"; from_x: number; from_y: number; to_x: number; to_y: number; color: Rgba8; stroke_width: number }
+  | { kind: "freehand"; points: [number, number][]; color: Rgba8; stroke_width: number }
+  | { kind: "step"; x: number; y: number; radius: number; color: Rgba8 };
+
+type RedactionMode = "pixelate" | "blur" | "solid";
+
+// Area scrubbed by save_screenshot before annotations are drawn, in selection pixels
+type Redaction = { kind: "redact"; x: number; y: number; width: number; height: number; mode: RedactionMode };
+
+// Everything drawn on the selection, in drawing order (for undo)
+type Mark = Annotation | Redaction;
+
+type Tool = Mark["kind"];
+
+const TOOLS: [Tool, string, string][] = [
+  ["arrow", "↗", "Arrow"],
+  ["rect", "▭", "Rectangle"],
+  ["ellipse", "◯", "Ellipse"],
+  ["freehand", "✎", "Pen"],
+  ["text", "A", "Text"],
+  ["step", "①", "Numbered step"],
+  ["redact", "▦", "Redact"],
+];
+
+const REDACTION_MODES: [RedactionMode, string][] = [
+  ["pixelate", "Pixelate"],
+  ["blur", "Blur"],
+  ["solid", "Solid"],
+];
+
+const STROKE_WIDTH = 3;
+const STEP_RADIUS = 12;
+const TEXT_SIZE = 18;
+
+function hexToRgba(hex: string): Rgba8 {
+  const n = parseInt(hex.slice(1), 16);
+  return [(n >> 16) & 255, (n >> 8) & 255, n & 255, 255];
+}
+
+function cssColor([r, g, b, a]: Rgba8): string {
+  return `rgba(${r}, ${g}, ${b}, ${a / 255})`;
+}
+
+// SVG preview of a mark; the backend draws the same shapes into the image.
+// Redactions are only outlined, as the overlay can't blur what's underneath.
+function AnnotationShape({ annotation: a, step }: { annotation: Mark; step: number }) {
+  switch (a.kind) {
+    case "redact":
+      return (
+        <rect
+          x={Math.min(a.x, a.x + a.width)}
+          y={Math.min(a.y, a.y + a.height)}
+          width={Math.abs(a.width)}
+          height={Math.abs(a.height)}
+          fill={a.mode === "solid" ? "#000" : "rgba(128, 128, 128, 0.7)"}
+          stroke="#fff"
+          strokeWidth={1}
+          strokeDasharray="4 3"
+        />
+      );
+    case "rect":
+      return (
+        <rect
+          x={Math.min(a.x, a.x + a.width)}
+          y={Math.min(a.y, a.y + a.height)}
+          width={Math.abs(a.width)}
+          height={Math.abs(a.height)}
+          fill={a.fill ? cssColor(a.fill) : "none"}
+          stroke={cssColor(a.color)}
+          strokeWidth={a.stroke_width}
+        />
+      );
+    case "ellipse":
+      return (
+        <ellipse
+          cx={a.x + a.width / 2}
+          cy={a.y + a.height / 2}
+          rx={Math.abs(a.width / 2)}
+          ry={Math.abs(a.height / 2)}
+          fill={a.fill ? cssColor(a.fill) : "none"}
+          stroke={cssColor(a.color)}
+          strokeWidth={a.stroke_width}
+        />
+      );
+    case "arrow": {
+      const back = Math.atan2(a.from_y - a.to_y, a.from_x - a.to_x);
+      const head = Math.max(a.stroke_width * 4, 10);
+      const wings = [back - Math.PI / 6, back + Math.PI / 6].map((angle) => [
+        a.to_x + head * Math.cos(angle),
+        a.to_y + head * Math.sin(angle),
+      ]);
+      return (
+        <g stroke={cssColor(a.color)} strokeWidth={a.stroke_width} strokeLinecap="round">
+          <line x1={a.from_x} y1={a.from_y} x2={a.to_x} y2={a.to_y} />
+          {wings.map(([x, y], i) => (
+            <line key={i} x1={a.to_x} y1={a.to_y} x2={x} y2={y} />
+          ))}
+        </g>
+      );
+    }
+    case "freehand":
+      return (
+        <polyline
+          points={a.points.map((p) => p.join(",")).join(" ")}
+          fill="none"
+          stroke={cssColor(a.color)}
+          strokeWidth={a.stroke_width}
+          strokeLinecap="round"
+          strokeLinejoin="round"
+        />
+      );
+    case "text":
+      return (
+        <text x={a.x} y={a.y} fontSize={a.font_size} fontWeight="bold" fill={cssColor(a.color)} dominantBaseline="hanging">
+          {a.text}
+        </text>
+      );
+    case "step":
+      return (
+        <g>
+          <circle cx={a.x} cy={a.y} r={a.radius} fill={cssColor(a.color)} />
+          <text
+            x={a.x}
+            y={a.y}
+            fontSize={a.radius * (step > 9 ? 0.9 : 1.2)}
+            fontWeight="bold"
+            fill="#fff"
+            textAnchor="middle"
+            dominantBaseline="central"
+          >
+            {step}
+          </text>
+        </g>
+      );
+  }
+}
+
 interface WindowInfo {
   x: number;
   y: number;
@@ -30,8 +192,16 @@ export default function Selector() {
   const [hoveredWindow, setHoveredWindow] = useState<SelectionRect | null>(null);
   const [resizeDir, setResizeDir] = useState<ResizeDirection>(null);
   const [excludeTitlebar, setExcludeTitlebar] = useState(false);
+  const [timelapseMs, setTimelapseMs] = useState(0);
+  const [timerSecs, setTimerSecs] = useState(0);
   const [currentTitlebarHeight, setCurrentTitlebarHeight] = useState(0);
   const [originalWindowInfo, setOriginalWindowInfo] = useState<WindowInfo | null>(null);
+  const [tool, setTool] = useState<Tool | null>(null);
+  const [toolColor, setToolColor] = useState("#ff3b30");
+  const [redactionMode, setRedactionMode] = useState<RedactionMode>("pixelate");
+  const [annotations, setAnnotations] = useState<Mark[]>([]);
+  const [draft, setDraft] = useState<Mark | null>(null);
+  const [editingText, setEditingText] = useState<number | null>(null);
 
   const startPos = useRef({ x: 0, y: 0 });
   const startRect = useRef<SelectionRect | null>(null);
@@ -43,15 +213,18 @@ export default function Selector() {
     await getCurrentWindow().close();
   }, []);
 
-  // Fetch pending mode from backend on mount
+  // Fetch pending mode (and the timed screenshot shortcut's delay) from backend on mount
   useEffect(() => {
-    invoke<Mode | null>("get_pending_mode").then((pendingMode) => {
-      console.log("[Selector] get_pending_mode 返回:", pendingMode);
-      if (pendingMode) {
-        setMode(pendingMode);
-        invoke("clear_pending_mode");
+    Promise.all([invoke<Mode | null>("get_pending_mode"), invoke<number | null>("get_pending_timer")]).then(
+      ([pendingMode, pendingTimer]) => {
+        console.log("[Selector] get_pending_mode 返回:", pendingMode, "timer:", pendingTimer);
+        if (pendingTimer) setTimerSecs(pendingTimer);
+        if (pendingMode) {
+          setMode(pendingMode);
+          invoke("clear_pending_mode");
+        }
       }
-    });
+    );
   }, []);
 
   // Track mouse position and detect window under cursor (throttled)
@@ -109,10 +282,21 @@ export default function Selector() {
       const win = getCurrentWindow();
       await win.hide();
       await new Promise((r) => setTimeout(r, 50));
-      await invoke("save_screenshot");
+      // Text annotations left empty aren't drawn
+      const marks = {
+        annotations: annotations.filter((a) => a.kind !== "redact" && (a.kind !== "text" || a.text.trim() !== "")),
+        redactions: annotations.filter((a) => a.kind === "redact"),
+      };
+      if (timerSecs > 0) {
+        // The backend counts down on the region overlay, then captures
+        await invoke("start_timed_screenshot", { delaySecs: timerSecs, ...marks });
+      } else {
+        await invoke("save_screenshot", marks);
+      }
       await win.close();
-    } else if (mode === "gif") {
-      await invoke("start_recording");
+    } else if (mode === "gif" || mode === "video") {
+      const timelapse = timelapseMs > 0 ? { interval_ms: timelapseMs, skip_identical: true } : null;
+      await invoke("start_recording", { mode, timelapse });
       await closeWindow();
     } else if (mode === "scroll") {
       // Scroll mode: hide selector, then open overlays and start capturing
@@ -140,7 +324,7 @@ export default function Selector() {
         }
       }
     }
-  }, [selectionRect, mode, closeWindow]);
+  }, [selectionRect, mode, timelapseMs, timerSecs, annotations, closeWindow]);
 
   // Resize handle start
   const handleResizeStart = useCallback(
@@ -154,22 +338,81 @@ export default function Selector() {
     [selectionRect]
   );
 
+  // Point relative to the selection, if it's inside
+  const toSelection = useCallback(
+    (e: React.MouseEvent): [number, number] | null => {
+      if (!selectionRect) return null;
+      const x = e.clientX - selectionRect.x;
+      const y = e.clientY - selectionRect.y;
+      return x >= 0 && y >= 0 && x <= selectionRect.w && y <= selectionRect.h ? [x, y] : null;
+    },
+    [selectionRect]
+  );
+
+  // Start an annotation with the active tool; returns false if the click isn't for it
+  const startAnnotation = useCallback(
+    (e: React.MouseEvent): boolean => {
+      const point = tool && mode === "image" ? toSelection(e) : null;
+      if (!tool || !point) return false;
+      const [x, y] = point;
+      const color = hexToRgba(toolColor);
+      if (tool === "text") {
+        setAnnotations((list) => [
+          ...list,
+          { kind: "text", x, y, text: "", font_size: TEXT_SIZE, color, background: null },
+        ]);
+        setEditingText(annotations.length);
+      } else if (tool === "step") {
+        setAnnotations((list) => [...list, { kind: "step", x, y, radius: STEP_RADIUS, color }]);
+      } else if (tool === "arrow") {
+        setDraft({ kind: "arrow", from_x: x, from_y: y, to_x: x, to_y: y, color, stroke_width: STROKE_WIDTH });
+      } else if (tool === "freehand") {
+        setDraft({ kind: "freehand", points: [[x, y]], color, stroke_width: STROKE_WIDTH });
+      } else if (tool === "redact") {
+        setDraft({ kind: "redact", x, y, width: 0, height: 0, mode: redactionMode });
+      } else {
+        setDraft({ kind: tool, x, y, width: 0, height: 0, color, stroke_width: STROKE_WIDTH, fill: null });
+      }
+      return true;
+    },
+    [tool, mode, toolColor, redactionMode, annotations.length, toSelection]
+  );
+
   // Mouse events
   const handleMouseDown = useCallback((e: React.MouseEvent) => {
     if ((e.target as HTMLElement).closest("#toolbar")) return;
     if ((e.target as HTMLElement).closest(".resize-handle")) return;
+    if ((e.target as HTMLElement).closest(".annotation-text-input")) return;
+    // The first click outside a text being typed just finishes it (on blur)
+    if (editingText !== null) return;
+    if (startAnnotation(e)) return;
 
     setShowToolbar(false);
+    setAnnotations([]);
+    setEditingText(null);
     setSelectionRect(null);
     setShowHint(false);
     // 不立即清除 hoveredWindow，让窗口高亮在拖拽时保持显示作为参考
 
     startPos.current = { x: e.clientX, y: e.clientY };
     setIsSelecting(true);
-  }, []);
+  }, [startAnnotation, editingText]);
 
   const handleMouseMove = useCallback(
     (e: React.MouseEvent) => {
+      if (draft && selectionRect) {
+        const x = Math.max(0, Math.min(selectionRect.w, e.clientX - selectionRect.x));
+        const y = Math.max(0, Math.min(selectionRect.h, e.clientY - selectionRect.y));
+        if (draft.kind === "arrow") {
+          setDraft({ ...draft, to_x: x, to_y: y });
+        } else if (draft.kind === "freehand") {
+          setDraft({ ...draft, points: [...draft.points, [x, y]] });
+        } else if (draft.kind === "rect" || draft.kind === "ellipse" || draft.kind === "redact") {
+          setDraft({ ...draft, width: x - draft.x, height: y - draft.y });
+        }
+        return;
+      }
+
       // Handle resize drag
       if (resizeDir && startRect.current) {
         const dx = e.clientX - startPos.current.x;
@@ -228,11 +471,24 @@ export default function Selector() {
         sizeRef.current.style.display = "block";
       }
     },
-    [isSelecting, resizeDir]
+    [isSelecting, resizeDir, draft, selectionRect]
   );
 
   const handleMouseUp = useCallback(
     async (e: React.MouseEvent) => {
+      if (draft) {
+        // Ignore clicks that didn't drag out a shape
+        const size =
+          draft.kind === "arrow"
+            ? Math.hypot(draft.to_x - draft.from_x, draft.to_y - draft.from_y)
+            : draft.kind === "rect" || draft.kind === "ellipse" || draft.kind === "redact"
+              ? Math.min(Math.abs(draft.width), Math.abs(draft.height))
+              : Infinity;
+        if (size >= 4) setAnnotations((list) => [...list, draft]);
+        setDraft(null);
+        return;
+      }
+
       // Handle resize end
       if (resizeDir) {
         setResizeDir(null);
@@ -291,7 +547,7 @@ export default function Selector() {
         }
       }
     },
-    [isSelecting, resizeDir, excludeTitlebar]
+    [isSelecting, resizeDir, excludeTitlebar, draft]
   );
 
   // Re-calculate selection when excludeTitlebar changes (only for window selections)
@@ -323,7 +579,12 @@ export default function Selector() {
   // Keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = async (e: KeyboardEvent) => {
-      if (e.key === "Escape") {
+      // Typing a text annotation
+      if (e.target instanceof HTMLInputElement) return;
+
+      if ((e.metaKey || e.ctrlKey) && (e.key === "z" || e.key === "Z")) {
+        setAnnotations((list) => list.slice(0, -1));
+      } else if (e.key === "Escape") {
         await closeWindow();
       } else if (e.key === "s" || e.key === "S") {
         setMode("image");
@@ -342,9 +603,32 @@ export default function Selector() {
     return () => document.removeEventListener("keydown", handleKeyDown);
   }, [selectionRect, doCapture, closeWindow]);
 
+  const finishText = () => setEditingText(null);
+
+  const editedText = editingText !== null ? annotations[editingText] : null;
+
+  // Offered for both GIF and video recordings
+  const timelapseSelect = (
+    <select
+      className="toolbar-select"
+      value={timelapseMs}
+      onChange={(e) => setTimelapseMs(parseInt(e.target.value))}
+      title="Timelapse: capture one frame per interval"
+    >
+      {TIMELAPSE_INTERVALS.map(([ms, label]) => (
+        <option key={ms} value={ms}>{label}</option>
+      ))}
+    </select>
+  );
+
+  // Approximate, to keep the toolbar on screen; annotation tools make it wider
+  const toolbarWidth = mode === "image" ? (tool === "redact" ? 720 : 660) : 200;
   const toolbarStyle: React.CSSProperties = selectionRect
     ? {
-        left: Math.max(10, Math.min(selectionRect.x + selectionRect.w / 2 - 100, window.innerWidth - 220)),
+        left: Math.max(
+          10,
+          Math.min(selectionRect.x + selectionRect.w / 2 - toolbarWidth / 2, window.innerWidth - toolbarWidth - 20)
+        ),
         top: Math.min(selectionRect.y + selectionRect.h + 12, window.innerHeight - 60),
       }
     : {};
@@ -380,6 +664,44 @@ export default function Selector() {
       <div ref={selectionRef} className="selection" />
       <div ref={sizeRef} className="size-label" />
 
+      {showToolbar && selectionRect && mode === "image" && (annotations.length > 0 || draft) && (
+        <svg
+          className="annotation-layer"
+          style={{ left: selectionRect.x, top: selectionRect.y, width: selectionRect.w, height: selectionRect.h }}
+        >
+          {[...annotations, ...(draft ? [draft] : [])].map((a, i, list) => (
+            <AnnotationShape
+              key={i}
+              annotation={a}
+              step={list.slice(0, i + 1).filter((b) => b.kind === "step").length}
+            />
+          ))}
+        </svg>
+      )}
+
+      {selectionRect && editedText?.kind === "text" && (
+        <input
+          className="annotation-text-input"
+          autoFocus
+          style={{
+            left: selectionRect.x + editedText.x,
+            top: selectionRect.y + editedText.y,
+            fontSize: editedText.font_size,
+            color: cssColor(editedText.color),
+          }}
+          value={editedText.text}
+          onChange={(e) =>
+            setAnnotations((list) =>
+              list.map((a, i) => (i === editingText && a.kind === "text" ? { ...a, text: e.target.value } : a))
+            )
+          }
+          onBlur={finishText}
+          onKeyDown={(e) => {
+            if (e.key === "Enter" || e.key === "Escape") finishText();
+          }}
+        />
+      )}
+
       {showToolbar && selectionRect && (
         <>
           {/* Edge handles */}
@@ -449,6 +771,7 @@ export default function Selector() {
           >
             G
           </button>
+          {mode === "gif" && timelapseSelect}
           <button
             className="toolbar-btn"
             disabled
@@ -465,6 +788,61 @@ export default function Selector() {
           >
             V
           </button>
+          {mode === "video" && timelapseSelect}
+          {mode === "image" && (
+            <select
+              className="toolbar-select"
+              value={timerSecs}
+              onChange={(e) => setTimerSecs(parseInt(e.target.value))}
+              title="Timer: capture after a delay, e.g. to open a menu or tooltip first"
+            >
+              {TIMER_OPTIONS.map(([secs, label]) => (
+                <option key={secs} value={secs}>{label}</option>
+              ))}
+            </select>
+          )}
+          {mode === "image" && (
+            <>
+              <div className="toolbar-divider" />
+              {TOOLS.map(([t, icon, label]) => (
+                <button
+                  key={t}
+                  className={`toolbar-btn ${tool === t ? "active" : ""}`}
+                  onClick={() => setTool(tool === t ? null : t)}
+                  title={`${label} - draw inside the selection`}
+                >
+                  {icon}
+                </button>
+              ))}
+              <input
+                type="color"
+                className="toolbar-color"
+                value={toolColor}
+                onChange={(e) => setToolColor(e.target.value)}
+                title="Annotation color"
+              />
+              {tool === "redact" && (
+                <select
+                  className="toolbar-select"
+                  value={redactionMode}
+                  onChange={(e) => setRedactionMode(e.target.value as RedactionMode)}
+                  title="Redaction style"
+                >
+                  {REDACTION_MODES.map(([m, label]) => (
+                    <option key={m} value={m}>{label}</option>
+                  ))}
+                </select>
+              )}
+              <button
+                className="toolbar-btn"
+                onClick={() => setAnnotations((list) => list.slice(0, -1))}
+                disabled={annotations.length === 0}
+                title="Undo (⌘Z)"
+              >
+                ↶
+              </button>
+            </>
+          )}
           <div className="toolbar-divider" />
           <button
             className={`toolbar-btn ${excludeTitlebar ? "active" : ""}`}
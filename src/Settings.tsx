@@ -8,11 +8,27 @@ interface ShortcutConfig {
   enabled: boolean;
 }
 
+interface RecordingConfig {
+  fps: number;
+  max_duration_secs: number; // 0 = unlimited
+  max_disk_mb: number; // compressed frames on disk, 0 = unlimited
+  log_keystrokes: boolean;
+}
+
+type MaskMode = "solid" | "blur" | "pixelate";
+
+interface ExclusionConfig {
+  apps: string[]; // app names or bundle IDs
+  mode: MaskMode;
+}
+
 interface AppConfig {
   version: string;
   shortcuts: Record<string, ShortcutConfig[]>;
   developer_mode: boolean;
   autostart_enabled: boolean;
+  recording: RecordingConfig;
+  exclusions: ExclusionConfig;
 }
 
 type EditingState = {
@@ -22,13 +38,40 @@ type EditingState = {
 
 const ACTION_LABELS: Record<string, string> = {
   screenshot: "Screenshot",
+  timed_screenshot: "Timed Screenshot",
   gif: "Record GIF",
   stop_recording: "Stop GIF (extra)",
+  pause_recording: "Pause / Resume GIF",
   video: "Record Video",
   scroll: "Scroll Capture",
   show_main: "Open Main Panel",
 };
 
+const FPS_OPTIONS = [10, 15, 20, 24, 30, 60];
+
+const DURATION_OPTIONS: [number, string][] = [
+  [30, "30 sec"],
+  [60, "1 min"],
+  [300, "5 min"],
+  [600, "10 min"],
+  [1800, "30 min"],
+  [0, "Unlimited"],
+];
+
+const MASK_OPTIONS: [MaskMode, string][] = [
+  ["solid", "Fill"],
+  ["blur", "Blur"],
+  ["pixelate", "Pixelate"],
+];
+
+const DISK_OPTIONS: [number, string][] = [
+  [512, "512 MB"],
+  [1024, "1 GB"],
+  [2048, "2 GB"],
+  [4096, "4 GB"],
+  [0, "Unlimited"],
+];
+
 function formatShortcut(cfg: ShortcutConfig): string {
   const mods = cfg.modifiers.map((m) => {
     switch (m.toLowerCase()) {
@@ -88,6 +131,7 @@ export default function Settings() {
   const [pendingShortcut, setPendingShortcut] = useState<{ modifiers: string[]; key: string } | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [debugInfo, setDebugInfo] = useState<string>("");
+  const [newExcludedApp, setNewExcludedApp] = useState("");
   const containerRef = useRef<HTMLDivElement>(null);
 
   // Load config on mount
@@ -214,6 +258,36 @@ export default function Settings() {
     }
   }, [config]);
 
+  const handleRecordingChange = useCallback(async (patch: Partial<RecordingConfig>) => {
+    if (!config) return;
+    try {
+      const newConfig = await invoke<AppConfig>("set_recording_config", {
+        recording: { ...config.recording, ...patch },
+      });
+      setConfig(newConfig);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [config]);
+
+  const handleExclusionsChange = useCallback(async (patch: Partial<ExclusionConfig>) => {
+    if (!config) return;
+    try {
+      const newConfig = await invoke<AppConfig>("set_exclusion_config", {
+        exclusions: { ...config.exclusions, ...patch },
+      });
+      setConfig(newConfig);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [config]);
+
+  const handleAddExcludedApp = () => {
+    if (!config || !newExcludedApp.trim()) return;
+    handleExclusionsChange({ apps: [...config.exclusions.apps, newExcludedApp] });
+    setNewExcludedApp("");
+  };
+
   const handleToggleAutostart = useCallback(async () => {
     if (!config) return;
     try {
@@ -235,8 +309,8 @@ export default function Settings() {
   }
 
   const actions = config.developer_mode
-    ? ["screenshot", "gif", "stop_recording", "scroll", "video", "show_main"]
-    : ["screenshot", "gif", "stop_recording", "video", "show_main"];
+    ? ["screenshot", "timed_screenshot", "gif", "stop_recording", "pause_recording", "scroll", "video", "show_main"]
+    : ["screenshot", "timed_screenshot", "gif", "stop_recording", "pause_recording", "video", "show_main"];
 
   return (
     <div className="settings-container" ref={containerRef} tabIndex={-1}>
@@ -347,6 +421,105 @@ export default function Settings() {
         </div>
       </section>
 
+      <section className="settings-section">
+        <h2 className="section-title">Recording</h2>
+        <div className="settings-card">
+          <div className="setting-row has-border">
+            <span className="setting-label">Frame Rate</span>
+            <select
+              className="shortcut-select"
+              value={config.recording.fps}
+              onChange={(e) => handleRecordingChange({ fps: Number(e.target.value) })}
+            >
+              {FPS_OPTIONS.map((fps) => (
+                <option key={fps} value={fps}>{fps} fps</option>
+              ))}
+            </select>
+          </div>
+          <div className="setting-row has-border">
+            <span className="setting-label">Max Duration</span>
+            <select
+              className="shortcut-select"
+              value={config.recording.max_duration_secs}
+              onChange={(e) => handleRecordingChange({ max_duration_secs: Number(e.target.value) })}
+            >
+              {DURATION_OPTIONS.map(([secs, label]) => (
+                <option key={secs} value={secs}>{label}</option>
+              ))}
+            </select>
+          </div>
+          <div className="setting-row has-border">
+            <span className="setting-label">Max Disk Usage</span>
+            <select
+              className="shortcut-select"
+              value={config.recording.max_disk_mb}
+              onChange={(e) => handleRecordingChange({ max_disk_mb: Number(e.target.value) })}
+            >
+              {DISK_OPTIONS.map(([mb, label]) => (
+                <option key={mb} value={mb}>{label}</option>
+              ))}
+            </select>
+          </div>
+          <div className="setting-row">
+            <span className="setting-label">Log Keystrokes</span>
+            <button
+              role="switch"
+              aria-checked={config.recording.log_keystrokes}
+              className={`switch ${config.recording.log_keystrokes ? "switch-on" : ""}`}
+              onClick={() => handleRecordingChange({ log_keystrokes: !config.recording.log_keystrokes })}
+            >
+              <span className="switch-thumb" />
+            </button>
+          </div>
+        </div>
+      </section>
+
+      <section className="settings-section">
+        <h2 className="section-title">Excluded Apps</h2>
+        <div className="settings-card">
+          <div className="setting-row has-border">
+            <span className="setting-label">Mask Style</span>
+            <select
+              className="shortcut-select"
+              value={config.exclusions.mode}
+              onChange={(e) => handleExclusionsChange({ mode: e.target.value as MaskMode })}
+            >
+              {MASK_OPTIONS.map(([mode, label]) => (
+                <option key={mode} value={mode}>{label}</option>
+              ))}
+            </select>
+          </div>
+          {config.exclusions.apps.map((app, idx) => (
+            <div key={app} className="setting-row has-border">
+              <span className="setting-label">{app}</span>
+              <button
+                className="btn-icon"
+                onClick={() =>
+                  handleExclusionsChange({ apps: config.exclusions.apps.filter((_, i) => i !== idx) })
+                }
+                title="Remove"
+              >
+                ×
+              </button>
+            </div>
+          ))}
+          <div className="setting-row">
+            <input
+              className="exclusion-input"
+              value={newExcludedApp}
+              placeholder="App name or bundle ID, e.g. 1Password"
+              onChange={(e) => setNewExcludedApp(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") handleAddExcludedApp();
+              }}
+            />
+            <button className="btn-icon" onClick={handleAddExcludedApp} title="Add app">
+              +
+            </button>
+          </div>
+        </div>
+      </section>
+
       <section className="settings-section">
         <h2 className="section-title">Advanced</h2>
         <div className="settings-card">